@@ -0,0 +1,164 @@
+mod expectimax;
+mod heuristic;
+mod mcts;
+
+use std::time::Duration;
+
+use crate::engine::{LudoEngine, Move, Position, Rang};
+
+use expectimax::{EvalWeights, ExpectimaxBot};
+use heuristic::{CaptureBot, FurthestBot, RandomBot, SafetyBot};
+use mcts::{Backup, MctsBot, MctsSettings};
+
+// A computer player, gets same information as a human would see on the screen
+pub trait Strategy {
+    fn name(&self) -> &str;
+
+    // Returns index of the chosen move
+    // Invariant: `options` is never empty, and is in same order as `LudoEngine::legal_moves()` (unlock first)
+    fn choose(&mut self, engine: &LudoEngine, colour: Rang, roll: u8, options: &[Move]) -> usize;
+
+    /**
+     * Returns index of the chosen (value, move) of `LudoEngine::get_plays()`, there may be more than one value to play (eg. two dice)
+     * By default the biggest value is played first, with `choose()` picking the move for it
+     * @note Searching bots look ahead as if a turn is always a single dice
+     */
+    fn choose_play(&mut self, engine: &LudoEngine, colour: Rang, plays: &[(u8, Move)]) -> usize {
+        let value = plays.iter().map(|(value, _)| *value).max().unwrap_or(0);
+        let options: Vec<Move> = plays
+            .iter()
+            .filter(|(v, _)| *v == value)
+            .map(|(_, mv)| mv.clone())
+            .collect();
+
+        let i = self.choose(engine, colour, value, &options).min(options.len() - 1);
+        plays
+            .iter()
+            .position(|play| *play == (value, options[i].clone()))
+            .unwrap()
+    }
+}
+
+// Typed after "bot" in place of a player name, eg. "bot capture"
+// Searching bots take settings after the kind, eg. "bot mcts 5000 1000" for 5000 playouts or 1 second
+//      expectimax [depth] [time limit in ms]
+//      mcts [iterations] [time limit in ms] [paranoid]
+pub const BOT_KINDS: [&str; 6] = ["random", "furthest", "capture", "safe", "expectimax", "mcts"];
+pub const DEFAULT_BOT: &str = "safe";
+
+// `seed` is only used by bots that need randomness
+// Returns None for unknown kind or invalid settings
+pub fn new_bot(spec: &str, seed: u64) -> Option<Box<dyn Strategy>> {
+    let mut words = spec.split_whitespace();
+    let kind = words.next().unwrap_or(DEFAULT_BOT);
+    let settings: Vec<&str> = words.collect();
+
+    let number = |i: usize, default: u64| -> Option<u64> {
+        match settings.get(i) {
+            Some(s) => s.parse().ok(),
+            None => Some(default),
+        }
+    };
+
+    match kind {
+        "random" | "furthest" | "capture" | "safe" if !settings.is_empty() => None,
+        "random" => Some(Box::new(RandomBot::new(seed))),
+        "furthest" => Some(Box::new(FurthestBot)),
+        "capture" => Some(Box::new(CaptureBot)),
+        "safe" => Some(Box::new(SafetyBot)),
+        "expectimax" if settings.len() <= 2 => Some(Box::new(ExpectimaxBot::new(
+            number(0, 3)? as u32,
+            Duration::from_millis(number(1, 2000)?),
+            EvalWeights::default(),
+        ))),
+        "mcts" if settings.len() <= 3 => {
+            let default = MctsSettings::default();
+            let backup = match settings.get(2) {
+                None => Backup::MaxN,
+                Some(&"paranoid") => Backup::Paranoid,
+                Some(_) => return None,
+            };
+
+            Some(Box::new(MctsBot::new(
+                MctsSettings {
+                    iterations: number(0, default.iterations as u64)? as u32,
+                    time_limit: Duration::from_millis(number(1, default.time_limit.as_millis() as u64)?),
+                    backup,
+                    ..default
+                },
+                seed,
+            )))
+        }
+        _ => None,
+    }
+}
+
+// What a move will do, so the bots don't each have to work it out
+pub struct OptionInfo {
+    pub unlocks: bool,
+    pub progress: u8,    // steps from start square of the goti, before moving (0 for locked)
+    pub captures: usize, // number of gotis captured
+    pub finishes: bool,
+    pub danger_before: usize, // enemy gotis that can capture the goti in their next roll
+    pub danger_after: usize,
+}
+
+impl OptionInfo {
+    pub fn new(engine: &LudoEngine, colour: Rang, mv: &Move) -> Self {
+        let (start, dest) = (mv.from(), mv.to());
+        let finishes = dest == Position::Finished;
+
+        OptionInfo {
+            unlocks: start == Position::Locked,
+            progress: start.progress().unwrap_or(0),
+            captures: match mv {
+                Move::Capture { victims, .. } => victims.len(),
+                _ => 0,
+            },
+            finishes,
+            // Locked & finished gotis are safe
+            danger_before: danger(engine, colour, start),
+            danger_after: danger(engine, colour, dest),
+        }
+    }
+}
+
+// Number of enemy gotis that can reach a goti of `colour` at `position` in one roll
+fn danger(engine: &LudoEngine, colour: Rang, position: Position) -> usize {
+    if engine.is_safe(colour, position) {
+        return 0;
+    }
+
+    let reaches = |enemy: Rang, goti: Position, dist: u8| {
+        matches!(engine.is_move_possible(enemy, goti, dist), Ok(dest) if dest.same_square(enemy, position, colour))
+    };
+
+    engine
+        .get_active_colours()
+        .iter()
+        .filter(|c| engine.is_enemy(colour, **c))
+        .flat_map(|enemy| {
+            engine
+                .get_positions(*enemy)
+                .into_iter()
+                .map(move |goti| (*enemy, goti))
+        })
+        .filter(|(enemy, goti)| (1..=6).any(|dist| reaches(*enemy, *goti, dist)))
+        .count()
+}
+
+// Index of option with highest score, first one if equal
+fn best_by<T>(options: &[T], mut score: impl FnMut(&T) -> i32) -> usize {
+    let mut best = 0;
+    let mut best_score = i32::MIN;
+
+    for (i, choice) in options.iter().enumerate() {
+        let s = score(choice);
+        if s > best_score {
+            best = i;
+            best_score = s;
+        }
+    }
+
+    best
+}
@@ -0,0 +1,168 @@
+use std::time::{Duration, Instant};
+
+use super::Strategy;
+use crate::engine::{LudoEngine, Move, MoveResult, Rang};
+
+// Weights of the evaluation, per goti of a colour
+#[derive(Clone, Debug)]
+pub struct EvalWeights {
+    pub step: f64,     // each step moved from the start square
+    pub unlocked: f64, // for being out of lock
+    pub safe: f64,     // for being on a safe spot or home lane
+    pub finished: f64,
+}
+
+impl Default for EvalWeights {
+    fn default() -> Self {
+        EvalWeights {
+            step: 1.0,
+            unlocked: 10.0,
+            safe: 4.0,
+            finished: 70.0,
+        }
+    }
+}
+
+// Score of every colour, indexed by `colour as usize`
+type Scores = [f64; 4];
+
+// Searches `depth` moves ahead, averaging over all 6 rolls at every chance node
+// Each colour maximises its own score relative to the others (max^n), so it works for 3-4 players too
+pub struct ExpectimaxBot {
+    depth: u32,
+    time_limit: Duration,
+    weights: EvalWeights,
+}
+
+impl ExpectimaxBot {
+    pub fn new(depth: u32, time_limit: Duration, weights: EvalWeights) -> Self {
+        ExpectimaxBot {
+            depth,
+            time_limit,
+            weights,
+        }
+    }
+
+    fn evaluate(&self, engine: &LudoEngine) -> Scores {
+        let mut material = [0.0; 4];
+        let colours = engine.get_active_colours();
+
+        for colour in colours {
+            let w = &self.weights;
+            let mut score = engine.get_num_finished(*colour) as f64 * w.finished;
+
+            for goti in engine.get_positions(*colour) {
+                score += w.unlocked
+                    + goti.progress().unwrap_or(0) as f64 * w.step
+                    + if engine.is_safe(*colour, goti) { w.safe } else { 0.0 };
+            }
+
+            material[*colour as usize] = score;
+        }
+
+        // Relative to the average of enemies, so helping no one in particular (a partner is as good as itself)
+        let mut scores = [0.0; 4];
+        for colour in colours {
+            let (enemies, own): (Vec<Rang>, Vec<Rang>) = colours.iter().partition(|c| engine.is_enemy(*colour, **c));
+            let sum = |colours: &[Rang]| -> f64 { colours.iter().map(|c| material[*c as usize]).sum() };
+
+            scores[*colour as usize] = sum(&own) - sum(&enemies) / enemies.len().max(1) as f64;
+        }
+
+        scores
+    }
+
+    // `engine` is the position after `colour` played `roll`
+    // Returns None if out of time
+    fn after_move(
+        &self,
+        engine: &LudoEngine,
+        colour: Rang,
+        roll: u8,
+        result: Option<&MoveResult>,
+        depth: u32,
+        deadline: Instant,
+    ) -> Option<Scores> {
+        if depth == 0 || engine.is_game_finished() {
+            return Some(self.evaluate(engine));
+        }
+
+        let next = if engine.is_bonus_turn(roll, result) && !engine.is_finished(colour) {
+            colour
+        } else {
+            engine.get_next_colour(colour)
+        };
+
+        self.chance(engine, next, depth, deadline)
+    }
+
+    // Average over the 6 possible rolls of `colour`
+    fn chance(&self, engine: &LudoEngine, colour: Rang, depth: u32, deadline: Instant) -> Option<Scores> {
+        let mut total = [0.0; 4];
+
+        for roll in 1..=6 {
+            let (scores, _) = self.decide(engine, colour, roll, depth, deadline)?;
+            for i in 0..4 {
+                total[i] += scores[i] / 6.0;
+            }
+        }
+
+        Some(total)
+    }
+
+    // Best option for `colour` with `roll`, and scores after it
+    fn decide(
+        &self,
+        engine: &LudoEngine,
+        colour: Rang,
+        roll: u8,
+        depth: u32,
+        deadline: Instant,
+    ) -> Option<(Scores, usize)> {
+        if Instant::now() > deadline {
+            return None;
+        }
+
+        let options = engine.legal_moves(colour, roll);
+        if options.is_empty() {
+            let scores = self.after_move(engine, colour, roll, None, depth - 1, deadline)?;
+            return Some((scores, 0));
+        }
+
+        let mut best: Option<(Scores, usize)> = None;
+        for (i, mv) in options.iter().enumerate() {
+            let mut child = engine.sandbox();
+            let result = child.play(colour, roll, mv).ok();
+
+            let scores = self.after_move(&child, colour, roll, result.as_ref(), depth - 1, deadline)?;
+
+            if best.is_none_or(|(b, _)| scores[colour as usize] > b[colour as usize]) {
+                best = Some((scores, i));
+            }
+        }
+
+        best
+    }
+}
+
+impl Strategy for ExpectimaxBot {
+    fn name(&self) -> &str {
+        "expectimax"
+    }
+
+    fn choose(&mut self, engine: &LudoEngine, colour: Rang, roll: u8, options: &[Move]) -> usize {
+        let deadline = Instant::now() + self.time_limit;
+        let mut chosen = 0;
+
+        // Iterative deepening, a search cut short by the time limit is thrown away
+        for depth in 1..=self.depth {
+            match self.decide(engine, colour, roll, depth, deadline) {
+                Some((_, i)) => chosen = i,
+                None => break,
+            }
+        }
+
+        // `decide()` finds options in the same order as `options`
+        chosen.min(options.len() - 1)
+    }
+}
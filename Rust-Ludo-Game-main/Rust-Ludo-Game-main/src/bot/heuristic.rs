@@ -0,0 +1,113 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use super::{best_by, OptionInfo, Strategy};
+use crate::engine::{LudoEngine, Move, Rang};
+
+// Any legal option
+pub struct RandomBot {
+    rng: ChaCha8Rng,
+}
+
+impl RandomBot {
+    pub fn new(seed: u64) -> Self {
+        RandomBot {
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Strategy for RandomBot {
+    fn name(&self) -> &str {
+        "random"
+    }
+
+    fn choose(&mut self, _engine: &LudoEngine, _colour: Rang, _roll: u8, options: &[Move]) -> usize {
+        self.rng.gen_range(0..options.len())
+    }
+
+    fn choose_play(&mut self, _engine: &LudoEngine, _colour: Rang, plays: &[(u8, Move)]) -> usize {
+        self.rng.gen_range(0..plays.len())
+    }
+}
+
+// Always moves the goti that is furthest ahead, unlocks only if nothing else can move
+pub struct FurthestBot;
+
+impl Strategy for FurthestBot {
+    fn name(&self) -> &str {
+        "furthest"
+    }
+
+    fn choose(&mut self, engine: &LudoEngine, colour: Rang, _roll: u8, options: &[Move]) -> usize {
+        best_by(options, |mv| Self::score(&OptionInfo::new(engine, colour, mv)))
+    }
+
+    fn choose_play(&mut self, engine: &LudoEngine, colour: Rang, plays: &[(u8, Move)]) -> usize {
+        best_by(plays, |(_, mv)| Self::score(&OptionInfo::new(engine, colour, mv)))
+    }
+}
+
+impl FurthestBot {
+    fn score(info: &OptionInfo) -> i32 {
+        if info.unlocks {
+            -1
+        } else {
+            info.progress as i32
+        }
+    }
+}
+
+// Captures whenever it can, then finishing, then unlocking, then furthest goti
+pub struct CaptureBot;
+
+impl Strategy for CaptureBot {
+    fn name(&self) -> &str {
+        "capture"
+    }
+
+    fn choose(&mut self, engine: &LudoEngine, colour: Rang, _roll: u8, options: &[Move]) -> usize {
+        best_by(options, |mv| Self::score(&OptionInfo::new(engine, colour, mv)))
+    }
+
+    fn choose_play(&mut self, engine: &LudoEngine, colour: Rang, plays: &[(u8, Move)]) -> usize {
+        best_by(plays, |(_, mv)| Self::score(&OptionInfo::new(engine, colour, mv)))
+    }
+}
+
+impl CaptureBot {
+    fn score(info: &OptionInfo) -> i32 {
+        info.captures as i32 * 1000
+            + if info.finishes { 500 } else { 0 }
+            + if info.unlocks { 100 } else { 0 }
+            + info.progress as i32
+    }
+}
+
+// Keeps its gotis out of reach of enemies, still takes a free capture or finish
+pub struct SafetyBot;
+
+impl Strategy for SafetyBot {
+    fn name(&self) -> &str {
+        "safe"
+    }
+
+    fn choose(&mut self, engine: &LudoEngine, colour: Rang, _roll: u8, options: &[Move]) -> usize {
+        best_by(options, |mv| Self::score(&OptionInfo::new(engine, colour, mv)))
+    }
+
+    fn choose_play(&mut self, engine: &LudoEngine, colour: Rang, plays: &[(u8, Move)]) -> usize {
+        best_by(plays, |(_, mv)| Self::score(&OptionInfo::new(engine, colour, mv)))
+    }
+}
+
+impl SafetyBot {
+    fn score(info: &OptionInfo) -> i32 {
+        // Escaping danger is as good as not walking into it
+        (info.danger_before as i32 - info.danger_after as i32) * 100
+            + info.captures as i32 * 80
+            + if info.finishes { 60 } else { 0 }
+            + if info.unlocks { 40 } else { 0 }
+            + info.progress as i32
+    }
+}
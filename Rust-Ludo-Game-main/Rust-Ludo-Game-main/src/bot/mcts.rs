@@ -0,0 +1,361 @@
+use std::time::{Duration, Instant};
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use super::Strategy;
+use crate::engine::{LudoEngine, Move, MoveResult, Rang, Sandbox};
+
+// How rewards of a playout are passed up the tree
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backup {
+    MaxN,     // every colour maximises its own reward
+    Paranoid, // every other colour plays against the bot
+}
+
+#[derive(Clone, Debug)]
+pub struct MctsSettings {
+    pub iterations: u32,      // stops after these many playouts...
+    pub time_limit: Duration, // ...or this much time, whichever comes first
+    pub exploration: f64,     // `c` of UCT
+    pub rollout_depth: u32,   // rolls in a playout before it is scored by progress
+    pub backup: Backup,
+}
+
+impl Default for MctsSettings {
+    fn default() -> Self {
+        MctsSettings {
+            iterations: 2000,
+            time_limit: Duration::from_secs(2),
+            exploration: 0.7,
+            rollout_depth: 60,
+            backup: Backup::MaxN,
+        }
+    }
+}
+
+// Reward of every colour, indexed by `colour as usize`
+type Rewards = [f64; 4];
+
+enum NodeKind {
+    // `colour` has rolled `roll`, children are one per move (or one for no move)
+    Decision {
+        colour: Rang,
+        roll: u8,
+        options: Vec<Move>,
+    },
+    // `colour` is about to roll, children are one per roll
+    Chance {
+        colour: Rang,
+    },
+}
+
+struct Node {
+    kind: NodeKind,
+    children: Vec<Option<usize>>, // index in `Tree::nodes`, None if not expanded yet
+    visits: u32,
+    total: Rewards,
+}
+
+struct Tree {
+    nodes: Vec<Node>,
+}
+
+impl Tree {
+    fn add(&mut self, kind: NodeKind) -> usize {
+        let num_children = match &kind {
+            NodeKind::Decision { options, .. } => options.len().max(1),
+            NodeKind::Chance { .. } => 6,
+        };
+
+        self.nodes.push(Node {
+            kind,
+            children: vec![None; num_children],
+            visits: 0,
+            total: [0.0; 4],
+        });
+        self.nodes.len() - 1
+    }
+}
+
+// Monte Carlo Tree Search, with chance nodes for the dice
+// A playout is cut short when some colour finishes (it is the winner, with its partner once both have finished in
+// `Rules::teams`), or after `rollout_depth` rolls
+pub struct MctsBot {
+    settings: MctsSettings,
+    rng: ChaCha8Rng,
+}
+
+impl MctsBot {
+    pub fn new(settings: MctsSettings, seed: u64) -> Self {
+        MctsBot {
+            settings,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    // First colour (or team) to finish since the search started, ends a playout
+    fn get_winner(engine: &LudoEngine, already_finished: &[Rang]) -> Option<Rang> {
+        engine
+            .get_active_colours()
+            .iter()
+            .find(|c| engine.is_team_finished(**c) && !already_finished.contains(c))
+            .copied()
+    }
+
+    // Full reward for `winner`, and its partner in `Rules::teams`
+    fn win(engine: &LudoEngine, winner: Rang) -> Rewards {
+        let mut rewards = [0.0; 4];
+        rewards[winner as usize] = 1.0;
+        if engine.get_rules().teams {
+            rewards[Rang::GetPartner(winner) as usize] = 1.0;
+        }
+        rewards
+    }
+
+    // Share of the total progress of the colours still playing
+    fn score(engine: &LudoEngine, already_finished: &[Rang]) -> Rewards {
+        let mut progress = [0.0; 4];
+        let mut sum = 0.0;
+
+        for colour in engine.get_active_colours() {
+            if already_finished.contains(colour) {
+                continue;
+            }
+
+            // +1 for being out of lock, so an unlocked goti is worth more than a locked one
+            // A finished goti is one step past the last square of home lane (55 steps)
+            let mut p = engine.get_num_finished(*colour) as f64 * 57.0;
+            for position in engine.get_positions(*colour) {
+                p += position.progress().unwrap_or(0) as f64 + 1.0;
+            }
+
+            progress[*colour as usize] = p;
+            sum += p;
+        }
+
+        if sum > 0.0 {
+            for p in progress.iter_mut() {
+                *p /= sum;
+            }
+        }
+
+        // Partners share their progress, one is as good as the other
+        if engine.get_rules().teams {
+            let own = progress;
+            for colour in engine.get_active_colours() {
+                progress[*colour as usize] += own[Rang::GetPartner(*colour) as usize];
+            }
+        }
+        progress
+    }
+
+    // Default policy, fast but better than random: finish or capture if possible, else any move
+    fn rollout_choice(&mut self, options: &[Move]) -> usize {
+        match options.iter().position(|mv| matches!(mv, Move::Finish { .. } | Move::Capture { .. })) {
+            Some(i) => i,
+            None => self.rng.gen_range(0..options.len()),
+        }
+    }
+
+    // Plays on `engine` from `colour` about to play (`roll` if it has already rolled)
+    fn rollout(
+        &mut self,
+        engine: &mut Sandbox,
+        mut colour: Rang,
+        mut roll: Option<u8>,
+        already_finished: &[Rang],
+    ) -> Rewards {
+        for _ in 0..self.settings.rollout_depth {
+            if let Some(winner) = MctsBot::get_winner(engine, already_finished) {
+                return MctsBot::win(engine, winner);
+            }
+
+            let dice = roll.take().unwrap_or_else(|| self.rng.gen_range(1..=6));
+            let options = engine.legal_moves(colour, dice);
+
+            let result = if options.is_empty() {
+                None
+            } else {
+                let i = self.rollout_choice(&options);
+                engine.play(colour, dice, &options[i]).ok()
+            };
+
+            colour = MctsBot::get_next(engine, colour, dice, result.as_ref());
+        }
+
+        match MctsBot::get_winner(engine, already_finished) {
+            Some(winner) => MctsBot::win(engine, winner),
+            None => MctsBot::score(engine, already_finished),
+        }
+    }
+
+    fn get_next(engine: &LudoEngine, colour: Rang, roll: u8, result: Option<&MoveResult>) -> Rang {
+        if engine.is_bonus_turn(roll, result) && !engine.is_finished(colour) {
+            colour
+        } else {
+            engine.get_next_colour(colour)
+        }
+    }
+
+    // UCT, from the view of colour to play at `node`
+    fn select(&self, tree: &Tree, node: usize, colour: Rang) -> usize {
+        let parent = &tree.nodes[node];
+        let log_visits = (parent.visits.max(1) as f64).ln();
+
+        let mut best = 0;
+        let mut best_value = f64::MIN;
+        for (i, child) in parent.children.iter().enumerate() {
+            // All children are expanded when selecting
+            let child = &tree.nodes[child.unwrap()];
+            let visits = child.visits.max(1) as f64;
+            let value = child.total[colour as usize] / visits
+                + self.settings.exploration * (log_visits / visits).sqrt();
+
+            if value > best_value {
+                best = i;
+                best_value = value;
+            }
+        }
+
+        best
+    }
+
+    // One selection, expansion, playout and backup
+    fn iterate(&mut self, tree: &mut Tree, root: &LudoEngine, me: Rang, already_finished: &[Rang]) {
+        let mut engine = root.sandbox();
+        let mut path = vec![0];
+        let mut node = 0;
+
+        // Where the playout starts from
+        let (colour, roll) = loop {
+            if MctsBot::get_winner(&engine, already_finished).is_some() {
+                break (me, None); // playout ends at once
+            }
+
+            match &tree.nodes[node].kind {
+                NodeKind::Decision {
+                    colour,
+                    roll,
+                    options,
+                } => {
+                    let (colour, roll) = (*colour, *roll);
+                    let unexpanded = tree.nodes[node].children.iter().position(|c| c.is_none());
+                    let i = unexpanded.unwrap_or_else(|| self.select(tree, node, colour));
+
+                    let result = options
+                        .get(i)
+                        .cloned()
+                        .and_then(|mv| engine.play(colour, roll, &mv).ok());
+                    let next = MctsBot::get_next(&engine, colour, roll, result.as_ref());
+
+                    let child = match tree.nodes[node].children[i] {
+                        Some(child) => child,
+                        None => {
+                            let child = tree.add(NodeKind::Chance { colour: next });
+                            tree.nodes[node].children[i] = Some(child);
+                            child
+                        }
+                    };
+                    path.push(child);
+                    node = child;
+
+                    if unexpanded.is_some() {
+                        break (next, None);
+                    }
+                }
+                NodeKind::Chance { colour } => {
+                    let colour = *colour;
+                    let roll = self.rng.gen_range(1..=6);
+
+                    match tree.nodes[node].children[roll as usize - 1] {
+                        Some(child) => {
+                            path.push(child);
+                            node = child;
+                        }
+                        None => {
+                            let options = engine.legal_moves(colour, roll);
+                            let child = tree.add(NodeKind::Decision {
+                                colour,
+                                roll,
+                                options,
+                            });
+                            tree.nodes[node].children[roll as usize - 1] = Some(child);
+                            path.push(child);
+
+                            break (colour, Some(roll));
+                        }
+                    }
+                }
+            }
+        };
+
+        let mut rewards = self.rollout(&mut engine, colour, roll, already_finished);
+
+        if self.settings.backup == Backup::Paranoid {
+            let mine = rewards[me as usize];
+            rewards = [1.0 - mine; 4];
+            rewards[me as usize] = mine;
+            if root.get_rules().teams {
+                rewards[Rang::GetPartner(me) as usize] = mine;
+            }
+        }
+
+        for node in path {
+            let node = &mut tree.nodes[node];
+            node.visits += 1;
+            for (total, reward) in node.total.iter_mut().zip(rewards) {
+                *total += reward;
+            }
+        }
+    }
+}
+
+impl Strategy for MctsBot {
+    fn name(&self) -> &str {
+        "mcts"
+    }
+
+    fn choose(&mut self, engine: &LudoEngine, colour: Rang, roll: u8, options: &[Move]) -> usize {
+        if options.len() == 1 {
+            return 0;
+        }
+
+        let deadline = Instant::now() + self.settings.time_limit;
+        let already_finished: Vec<Rang> = engine
+            .get_active_colours()
+            .iter()
+            .filter(|c| engine.is_finished(**c))
+            .copied()
+            .collect();
+
+        let mut tree = Tree { nodes: Vec::new() };
+        tree.add(NodeKind::Decision {
+            colour,
+            roll,
+            options: options.to_vec(),
+        });
+
+        for _ in 0..self.settings.iterations {
+            if Instant::now() > deadline {
+                break;
+            }
+            self.iterate(&mut tree, engine, colour, &already_finished);
+        }
+
+        // Most visited option, it is the most trusted one
+        let root = &tree.nodes[0];
+        let mut chosen = 0;
+        let mut most_visits = 0;
+        for (i, child) in root.children.iter().enumerate() {
+            if let Some(child) = child {
+                if tree.nodes[*child].visits > most_visits {
+                    chosen = i;
+                    most_visits = tree.nodes[*child].visits;
+                }
+            }
+        }
+
+        chosen
+    }
+}
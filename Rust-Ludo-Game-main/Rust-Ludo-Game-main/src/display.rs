@@ -28,7 +28,7 @@ impl Display {
         let hor_char = "─";
         let vert_char = "│";
 
-        let message = "Enter names of the Players (Leave empty if not playing)";
+        let message = "Enter names of the Players (Leave empty if not playing, \"bot <kind>\" for computer)";
         let mut names = [String::new(), String::new(), String::new(), String::new()];
         let colors = ["🔴","🟢","🟡","🔵"];
 
@@ -102,12 +102,37 @@ impl Display {
         }
     }
 
+    // Shown once the game is over, `lines` one below the other under `title`
+    pub fn standings_screen(&self, title: &str, lines: &[String]) {
+        let (columns, rows) = terminal::size().unwrap();
+        let mut stdout = stdout();
+
+        self.header();
+
+        let top = (rows as usize).saturating_sub(lines.len() + 2) as u16 / 2;
+        let width = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+
+        stdout
+            .queue(cursor::MoveTo(((columns as usize).saturating_sub(title.chars().count()) / 2) as u16, top)).unwrap()
+            .queue(style::PrintStyledContent(title.bold().with(Color::Green))).unwrap();
+
+        for (i, line) in lines.iter().enumerate() {
+            stdout
+                .queue(cursor::MoveTo(((columns as usize).saturating_sub(width) / 2) as u16, top + 2 + i as u16)).unwrap()
+                .queue(style::Print(line)).unwrap();
+        }
+
+        // Anything printed after goes below the standings
+        stdout.queue(cursor::MoveTo(0, top + 3 + lines.len() as u16)).unwrap();
+        stdout.flush().unwrap();
+    }
+
     pub fn set_player(&mut self, name: &str) {
         self.player_name = name.to_string();
     }
 
     // This way, all 3 components: game, engine & display are separate
-    pub fn update_display(&self, board_contents: Vec<((u8,u8), String)> ) {
+    pub fn update_display(&self, board_contents: Vec<((u8,u8), String, bool)> ) {
         Display::ensure_terminal_size();
     
         let player_name = &self.player_name;
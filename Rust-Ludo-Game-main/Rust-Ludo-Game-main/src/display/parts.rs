@@ -61,7 +61,7 @@ impl Display {
 
         // Left Margin
         stdout
-            .queue(cursor::MoveToColumn(left_spacing as u16 + 1 + (h_scale / 2))).unwrap();
+            .queue(cursor::MoveToColumn(left_spacing + 1 + (h_scale / 2))).unwrap();
 
         for i in 0..15 {
             stdout
@@ -76,7 +76,7 @@ impl Display {
         stdout.queue(cursor::MoveToNextLine(1)).unwrap();
 
         let board_start_row = cursor::position().unwrap().1;
-        let board_start_col = left_spacing as u16;
+        let board_start_col = left_spacing;
 
         stdout
             .queue(cursor::MoveToColumn(left_spacing)).unwrap()
@@ -245,24 +245,22 @@ impl Display {
         board_start_row: u16,
         h_scale: u16,
         v_scale: u16,
-        board_contents: Vec<((u8, u8), String)>,
+        board_contents: Vec<((u8, u8), String, bool)>, // (coord, content, safe square)
     ) {
         // START: Board Content
         let mut stdout = stdout();
     
-        for ((r, c), mut cell) in board_contents {
+        for ((r, c), mut cell, safe) in board_contents {
             // If this has a value, then print it with that color's background
             let mut color = Option::None;
     
             // Inner Square
-            if r >= 6 && r <= 8 && c >= 6 && c <= 8 {
+            if (6..=8).contains(&r) && (6..=8).contains(&c) {
                 continue;
             }
     
-            // Safe Spots
-            if [(1, 8), (2, 6), (6, 1), (6, 12), (8, 2), (8, 13), (12, 8), (13, 6)]
-                .contains(&(r, c))
-            {
+            // Safe Spots, which ones depends on `Rules::safe_spots`
+            if safe {
                 // Safe spots will have a grey background
                 color = Some(Color::Grey);
             }
@@ -321,8 +319,8 @@ impl Display {
     
             stdout
                 .queue(cursor::MoveTo(
-                    (board_start_col + (c as u16) * (h_scale + 1) + (h_scale / 2)).into(),
-                    (board_start_row + (r as u16) * (v_scale + 1) + 3) as u16, // Adjusted by +3
+                    board_start_col + (c as u16) * (h_scale + 1) + (h_scale / 2),
+                    board_start_row + (r as u16) * (v_scale + 1) + 3, // Adjusted by +3
                 ))
                 .unwrap();
     
@@ -338,6 +336,7 @@ impl Display {
     }
     
 
+    #[allow(clippy::too_many_arguments)]
     fn color_boxes(
         &self,
         board_start_col: u16,
@@ -377,8 +376,8 @@ impl Display {
                 for i in 0..v_scale {
                     stdout
                         .queue(cursor::MoveTo(
-                            (board_start_col + col_start * (h_scale + 1)).into(),
-                            (board_start_row + (r + 1) * (v_scale + 1) + i + 2).into(),
+                            board_start_col + col_start * (h_scale + 1),
+                            board_start_row + (r + 1) * (v_scale + 1) + i + 2,
                         ))
                         .unwrap()
                         .queue(style::PrintStyledContent(styled_blocks.clone()))
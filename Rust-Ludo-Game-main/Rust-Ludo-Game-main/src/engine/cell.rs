@@ -1,5 +1,3 @@
-use std::{cell::RefCell, rc::Rc};
-
 use super::goti::LudoGoti;
 use super::rang::Rang;
 
@@ -13,7 +11,8 @@ pub enum LudoCellType {
     NoUse   // MUST not be mutated, such a cell will panic on invalid (eg. movedHere etc.)
 }
 
+// A square of the board as it is drawn, see `LudoEngine::get_board()`
 pub struct LudoCell {
     pub cell_type: LudoCellType,
-    pub gotis: Vec<Rc<RefCell<LudoGoti>>>
+    pub gotis: Vec<LudoGoti>
 }
@@ -1,5 +1,215 @@
-use rand::Rng;
+use rand::{distributions::WeightedIndex, prelude::Distribution, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 
-pub fn roll() -> u8 {
-    rand::thread_rng().gen_range(1..7)
+use super::LudoError;
+
+// Source of dice rolls for the engine
+// Note: `seed()` is what gets recorded, so a game can be replayed exactly with same rolls
+// Send + Sync, so that an engine can be moved to other threads (eg. simulations)
+pub trait Dice: Send + Sync {
+    fn roll(&mut self) -> u8;
+
+    // None, if the rolls can not be reproduced (eg. ThreadDice)
+    fn seed(&self) -> Option<u64> {
+        None
+    }
+
+    // For cloning the engine, a clone rolls the same as the original from here on
+    fn box_clone(&self) -> Box<dyn Dice>;
+}
+
+impl Clone for Box<dyn Dice> {
+    fn clone(&self) -> Self {
+        self.box_clone()
+    }
+}
+
+// Old behaviour, just calls `rand::thread_rng()`, NOT reproducible
+#[derive(Clone)]
+pub struct ThreadDice;
+
+impl Dice for ThreadDice {
+    fn roll(&mut self) -> u8 {
+        rand::thread_rng().gen_range(1..7)
+    }
+
+    fn box_clone(&self) -> Box<dyn Dice> {
+        Box::new(self.clone())
+    }
+}
+
+// Why ChaCha8Rng ? Because, unlike StdRng, its output is same across platforms & rand versions
+#[derive(Clone)]
+pub struct SeededDice {
+    seed: u64,
+    rng: ChaCha8Rng,
+}
+
+impl SeededDice {
+    pub fn new(seed: u64) -> Self {
+        SeededDice {
+            seed,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    // Picks a random seed, but still records it, so the game can be replayed later
+    pub fn from_entropy() -> Self {
+        SeededDice::new(rand::thread_rng().gen())
+    }
+}
+
+impl Dice for SeededDice {
+    fn roll(&mut self) -> u8 {
+        self.rng.gen_range(1..7)
+    }
+
+    fn seed(&self) -> Option<u64> {
+        Some(self.seed)
+    }
+
+    fn box_clone(&self) -> Box<dyn Dice> {
+        Box::new(self.clone())
+    }
+}
+
+// Returns the given rolls in order, and starts again from first once all are used
+// Mostly for testing, eg. ScriptedDice::new(vec![6, 1]) to unlock then move 1 step
+#[derive(Clone)]
+pub struct ScriptedDice {
+    rolls: Vec<u8>,
+    next: usize,
+}
+
+impl ScriptedDice {
+    pub fn new(rolls: Vec<u8>) -> Result<Self, LudoError> {
+        if rolls.is_empty() || rolls.iter().any(|r| !(1..=6).contains(r)) {
+            return Err(LudoError::InvalidDice(format!(
+                "Scripted rolls must be non-empty and between 1 and 6: {:?}",
+                rolls
+            )));
+        }
+
+        Ok(ScriptedDice { rolls, next: 0 })
+    }
+}
+
+impl Dice for ScriptedDice {
+    fn roll(&mut self) -> u8 {
+        let roll = self.rolls[self.next];
+        self.next = (self.next + 1) % self.rolls.len();
+
+        roll
+    }
+
+    fn box_clone(&self) -> Box<dyn Dice> {
+        Box::new(self.clone())
+    }
+}
+
+// Biased dice, `weights[i]` is the relative chance of rolling `i+1`
+#[derive(Clone)]
+pub struct LoadedDice {
+    seed: u64,
+    rng: ChaCha8Rng,
+    weights: WeightedIndex<u32>,
+}
+
+impl LoadedDice {
+    pub fn new(weights: [u32; 6], seed: u64) -> Result<Self, LudoError> {
+        let weights = WeightedIndex::new(weights)
+            .map_err(|_| LudoError::InvalidDice("Atleast one face must have non-zero weight".to_string()))?;
+
+        Ok(LoadedDice {
+            seed,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            weights,
+        })
+    }
+}
+
+impl Dice for LoadedDice {
+    fn roll(&mut self) -> u8 {
+        self.weights.sample(&mut self.rng) as u8 + 1
+    }
+
+    fn seed(&self) -> Option<u64> {
+        Some(self.seed)
+    }
+
+    fn box_clone(&self) -> Box<dyn Dice> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{LudoEngine, Move, Position, Rang, Rules, TurnState};
+
+    // Plays the first of the plays every time, for `rolls` rolls
+    fn play_first(engine: &mut LudoEngine, rolls: u32) {
+        for _ in 0..rolls {
+            engine.roll().unwrap();
+            while engine.get_turn_state() == TurnState::AwaitingMove {
+                match engine.get_plays().first() {
+                    Some((value, mv)) => engine.choose(*value, mv).map(|_| ()).unwrap(),
+                    None => engine.pass().unwrap(),
+                }
+            }
+            if engine.get_turn_state() == TurnState::TurnOver {
+                engine.end_turn().unwrap();
+            }
+        }
+    }
+
+    // Red unlocks on the 6 and moves 3 in its bonus turn, Green can't move a 2, then Red moves 4
+    #[test]
+    fn scripted_game() {
+        let dice = Box::new(ScriptedDice::new(vec![6, 3, 2, 4]).unwrap());
+        let mut engine = LudoEngine::new(vec![Rang::Red, Rang::Green], Rules::default(), dice).unwrap();
+
+        assert_eq!(engine.roll().unwrap(), [6]);
+        engine.choose(6, &Move::Unlock(0)).unwrap();
+        engine.end_turn().unwrap();
+        assert_eq!(engine.current_player(), Rang::Red);
+
+        assert_eq!(engine.roll().unwrap(), [3]);
+        engine.choose(3, &Move::Advance { from: Position::Track(0), to: Position::Track(3) }).unwrap();
+        engine.end_turn().unwrap();
+        assert_eq!(engine.current_player(), Rang::Green);
+
+        assert_eq!(engine.roll().unwrap(), [2]);
+        assert!(engine.get_plays().is_empty());
+        engine.pass().unwrap();
+        engine.end_turn().unwrap();
+
+        assert_eq!(engine.roll().unwrap(), [4]);
+        engine.choose(4, &Move::Advance { from: Position::Track(3), to: Position::Track(7) }).unwrap();
+        engine.end_turn().unwrap();
+
+        assert_eq!(engine.get_positions(Rang::Red), [Position::Track(7)]);
+        assert!(engine.get_positions(Rang::Green).is_empty());
+        assert_eq!(engine.to_notation(), "R:L,L,L,7/G:L,L,L,L G 0/0");
+    }
+
+    // Same seed, same game... the seed is all that's needed to replay it
+    #[test]
+    fn seeded_games_repeat() {
+        let new_engine = |seed: u64| {
+            let colours = vec![Rang::Red, Rang::Green, Rang::Yellow, Rang::Blue];
+            LudoEngine::new(colours, Rules::default(), Box::new(SeededDice::new(seed))).unwrap()
+        };
+
+        let mut first = new_engine(42);
+        let mut second = new_engine(42);
+        let mut other = new_engine(43);
+        play_first(&mut first, 200);
+        play_first(&mut second, 200);
+        play_first(&mut other, 200);
+
+        assert_eq!(first.get_seed(), Some(42));
+        assert_eq!(first.to_notation(), second.to_notation());
+        assert_ne!(first.to_notation(), other.to_notation());
+    }
 }
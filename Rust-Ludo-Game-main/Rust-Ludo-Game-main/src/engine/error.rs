@@ -0,0 +1,51 @@
+use std::fmt;
+
+use super::{Move, Position, Rang, TurnState};
+
+// Everything that can go wrong in the engine, its public methods return these instead of panicking
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LudoError {
+    NoActiveColours,
+    RepeatedColour(Rang),
+    ColourNotPlaying(Rang),
+    NotYourTurn(Rang),
+    GameOver,
+    NotOnRoute(Position), // out of range, eg. `Position::Track(60)`, no goti can ever be there
+    NoGotiAt(Rang, Position),
+    MoveNotPossible(Position, u8), // goti at the position can't move by these many steps
+    IllegalMove(Move),             // not one of `LudoEngine::legal_moves()`
+    MustMove,                      // can't pass the turn with a move possible
+    InvalidRoll(Vec<u8>),          // not a roll of the dice in the rules
+    WrongTurnState(TurnState),     // not the step of the turn it is in
+    UnknownColour(char),
+    InvalidRules(String),
+    InvalidPosition(String), // notation or saved state that isn't a valid game
+    InvalidDice(String),
+}
+
+impl fmt::Display for LudoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LudoError::NoActiveColours => write!(f, "No active colours to play"),
+            LudoError::RepeatedColour(colour) => write!(f, "Colour {:?} is repeated", colour),
+            LudoError::ColourNotPlaying(colour) => write!(f, "{:?} is not playing", colour),
+            LudoError::NotYourTurn(colour) => write!(f, "Not {:?}'s turn", colour),
+            LudoError::GameOver => write!(f, "Game is already over"),
+            LudoError::NotOnRoute(position) => write!(f, "Not a position on the route: {:?}", position),
+            LudoError::NoGotiAt(colour, position) => {
+                write!(f, "Goti of colour: {:?} doesn't exist at {:?}", colour, position)
+            }
+            LudoError::MoveNotPossible(position, dist) => write!(f, "Move not possible: {:?} by {}", position, dist),
+            LudoError::IllegalMove(mv) => write!(f, "Move not allowed by the rules: {:?}", mv),
+            LudoError::MustMove => write!(f, "Can't pass, a move is possible"),
+            LudoError::InvalidRoll(dice) => write!(f, "Not a roll of the dice: {:?}", dice),
+            LudoError::WrongTurnState(state) => write!(f, "Not allowed now, turn is in {:?}", state),
+            LudoError::UnknownColour(letter) => write!(f, "Unknown colour: {:?}", letter),
+            LudoError::InvalidRules(e) => write!(f, "Invalid rules: {}", e),
+            LudoError::InvalidPosition(e) => write!(f, "Invalid position: {}", e),
+            LudoError::InvalidDice(e) => write!(f, "Invalid dice: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for LudoError {}
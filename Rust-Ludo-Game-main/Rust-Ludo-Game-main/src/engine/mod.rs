@@ -1,45 +1,125 @@
 use array_init::array_init;
-use std::{cell::RefCell, collections::BTreeMap as Map, rc::Rc};
+use std::{
+    collections::BTreeMap as Map,
+    hash::{Hash, Hasher},
+};
 
 mod cell;
 pub mod dice;
+mod error;
 mod goti;
+mod moves;
+mod notation;
+mod path;
 mod rang;
+mod rules;
+mod sandbox;
+mod sixes;
+mod state;
+mod turn;
 
 use self::{
-    cell::{LudoCell as Box, LudoCellType},
+    cell::LudoCell as Box,
+    dice::Dice,
     goti::LudoGoti,
 };
+pub use cell::LudoCellType;
+pub use error::LudoError;
+pub use moves::Move;
+pub use path::Position;
 pub use rang::Rang; 
+pub use rules::{FinishMode, Rules, SafeSpots, SixesPenalty};
+pub use sandbox::Sandbox;
+pub use state::EngineState;
+pub use turn::TurnState;
+
+use path::Square;
+use turn::Turn;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum MoveResult {
-    NormalMove((u8, u8)), // normal move
-    Attacked((u8, u8)),   // attacked a goti already present there
+    NormalMove(Position), // normal move
+    Attacked(Position),   // attacked a goti already present there
     Unlocked,             // goti was unlocked
     Finished,             // goti finished move
 }
 
+// Plain data, so it can be cloned (eg. for search) & sent to other threads
+// Equality & hash are of the position only, ie. not the dice (nor how many times it was rolled) nor the turn in progress
+// Which goti of a colour is where doesn't matter either, see `get_sorted_gotis()`
+#[derive(Clone)]
 pub struct LudoEngine {
-    board: [[Box; 15]; 15],
-
-    // Note1: Whenever needs to share goti, .clone() the Rc, else RefCell will be in shared state and .borrow_mut() may panic
-    // Note2: ONLY use .borrow_mut(), at the moment you are making a change, else a non-mutable function call that tries to .borrow() may panic
-    // Why Rc<RefCell<>> ? Because, RefCell can't be cloned (I dont want to derive Clone on LudoGoti)
-    moving_gotis: Map<Rang, Vec<Rc<RefCell<LudoGoti>>>>,
-    locked_gotis: Map<Rang, Vec<Rc<RefCell<LudoGoti>>>>,
-    num_finished: Map<Rang, u8>,
+    // Every goti of every playing colour, index is the id of the goti & never changes
+    // Note: Ids of a position depend on how it was reached, eg. an unlock takes the first locked id
+    gotis: Vec<LudoGoti>,
+    num_captures: Map<Rang, u32>, // gotis captured by each colour
     active_colours: Vec<Rang>,
-    curr_colour: Rang,
+    curr_player: Rang, // whose turn it is, see `current_player()`
+    curr_colour: Rang, // colour it moves, see `get_playing_colour()`
+    turn: Turn,
+    dice: std::boxed::Box<dyn Dice>, // `Box` is the cell here
+    num_rolls: u64,                  // so that a seeded dice can be fast-forwarded on resume
+    num_rounds: u32,                 // every colour has played its turn these many times, for `Rules::rounds`
+    finish_order: Vec<Rang>,         // colours in the order they finished
+    rules: Rules,
 }
 
 impl LudoEngine {
-    pub fn new(active_colours: Vec<Rang>) -> Self {
+    pub fn new(active_colours: Vec<Rang>, rules: Rules, dice: std::boxed::Box<dyn Dice>) -> Result<Self, LudoError> {
         if active_colours.is_empty() {
-            panic!("No active colours to play 🥲");
+            return Err(LudoError::NoActiveColours);
+        }
+        for (i, colour) in active_colours.iter().enumerate() {
+            if active_colours[..i].contains(colour) {
+                return Err(LudoError::RepeatedColour(*colour));
+            }
+        }
+        rules.validate().map_err(LudoError::InvalidRules)?;
+
+        let mut gotis = Vec::new();
+        let mut num_captures = Map::new();
+        for colour in active_colours.iter() {
+            let colour = *colour;
+            for _ in 0..rules.gotis_per_colour {
+                gotis.push(LudoGoti { colour, position: Position::Locked });
+            }
+            num_captures.insert(colour, 0);
         }
 
-        
+        Ok(LudoEngine {
+            curr_player: active_colours[0],
+            curr_colour: active_colours[0],
+            active_colours,
+            turn: Turn::default(),
+            gotis,
+            num_captures,
+            dice,
+            num_rolls: 0,
+            num_rounds: 0,
+            finish_order: Vec::new(),
+            rules,
+        })
+    }
+
+    // A single dice, see `roll()` for the roll of a turn
+    fn roll_once(&mut self) -> u8 {
+        self.num_rolls += 1;
+        self.dice.roll()
+    }
+
+    // Note: Recorded seed of the dice, None if the dice isn't reproducible
+    pub fn get_seed(&self) -> Option<u64> {
+        self.dice.seed()
+    }
+
+    pub fn get_rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    // The board as it is drawn, with gotis on their squares (locked ones in the first locked positions of their colour)
+    // Note: Built on every call, the engine itself only knows positions along the route, see `Position`
+    #[allow(clippy::needless_range_loop)]
+    pub fn get_board(&self) -> [[Box; 15]; 15] {
         let mut board: [[Box; 15]; 15] = array_init(|_| {
             array_init(|_| Box {
                 cell_type: LudoCellType::NoUse,
@@ -47,519 +127,651 @@ impl LudoEngine {
             })
         });
 
-        // Order Matters: For eg. Default also marks some as SafeSpots
-        // Defaults (ie. usual Path box)
-        {
-            for r in 6..9 {
-                for c in 0..15 {
-                    board[r][c].cell_type = LudoCellType::Default;
-                    board[c][r].cell_type = LudoCellType::Default;
-                }
+        for r in 0..15 {
+            for c in 0..15 {
+                board[r][c].cell_type = match Square::from_coord((r as u8, c as u8)) {
+                    Some(Square::HomeLane(colour, _)) => LudoCellType::HomeLane(colour),
+                    Some(square) if self.is_safe_square(square) => LudoCellType::SafeSpot,
+                    Some(_) => LudoCellType::Default,
+                    None => LudoCellType::NoUse,
+                };
             }
+        }
+
+        for colour in [Rang::Red, Rang::Green, Rang::Yellow, Rang::Blue] {
+            let locked: Vec<&LudoGoti> = self.gotis_of(colour).filter(|g| g.position == Position::Locked).collect();
 
-            // Mark middle square as NoUse again
-            for r in 6..9 {
-                for c in 6..9 {
-                    board[r][c].cell_type = LudoCellType::NoUse;
+            for (i, (r, c)) in Rang::GetLockedPositions(colour).into_iter().enumerate() {
+                if self.active_colours.contains(&colour) {
+                    board[r as usize][c as usize].cell_type = LudoCellType::LockedPosition(colour);
+                }
+                if let Some(goti) = locked.get(i) {
+                    board[r as usize][c as usize].gotis.push(**goti);
                 }
             }
         }
 
-        // Safe Spots
-        for (r, c) in [
-            (1, 8),
-            (2, 6),
-            (6, 1),
-            (6, 12),
-            (8, 2),
-            (8, 13),
-            (12, 8),
-            (13, 6),
-        ] {
-            board[r][c].cell_type = LudoCellType::SafeSpot;
+        for goti in self.gotis.iter() {
+            if let Some((r, c)) = goti.position.square(goti.colour).map(Square::coord) {
+                board[r as usize][c as usize].gotis.push(*goti);
+            }
         }
 
-        let mut locked_gotis = Map::new();
-        // Locked Locations:
-        for colour in active_colours.iter() {
-            let colour = *colour;
-            locked_gotis.insert(colour, Vec::new());
-
-            for (r, c) in Rang::GetLockedPositions(colour) {
-                board[r as usize][c as usize].cell_type = LudoCellType::LockedPosition(colour);
+        board
+    }
 
-                let goti_ref = Rc::new(RefCell::new(LudoGoti {
-                    colour,
-                    coords: (r, c),
-                }));
+    // In order of play
+    pub fn get_active_colours(&self) -> &[Rang] {
+        &self.active_colours
+    }
 
-                locked_gotis
-                    .get_mut(&colour)
-                    .unwrap()
-                    .push(goti_ref.clone());
+    /** @note Will always return `true` for a colour that is not playing */
+    // Finished all its gotis, or as many as `Rules::gotis_to_win`
+    pub(crate) fn is_finished(&self, colour: Rang) -> bool {
+        if !self.active_colours.contains(&colour) {
+            true
+        } else {
+            let to_win = match self.rules.gotis_to_win {
+                0 => self.rules.gotis_per_colour,
+                n => n,
+            };
 
-                // board should know also
-                board[r as usize][c as usize].gotis.push(goti_ref);
-            }
+            self.get_num_finished(colour) >= to_win
         }
+    }
 
-        // Home Lane (the ending path to finish)
-        {
-            // Red
-            for r in 9..14 {
-                board[r][7].cell_type = LudoCellType::HomeLane(Rang::Red);
-            }
+    // Colour that moves in this turn, the current player's own unless it plays for its partner
+    pub fn get_current_colour(&self) -> Rang {
+        self.curr_colour
+    }
 
-            // Green
-            for c in 1..6 {
-                board[7][c].cell_type = LudoCellType::HomeLane(Rang::Green);
-            }
+    // If `colour` can bring a goti out of lock with `roll`
+    pub fn can_unlock(&self, colour: Rang, roll: u8) -> bool {
+        self.rules.unlock_rolls.contains(&roll)
+            && self.get_num_locked(colour).unwrap_or(0) > 0
+            && !self.is_blocked(colour, Square::start(colour))
+    }
 
-            // Yellow
-            for r in 1..6 {
-                board[r][7].cell_type = LudoCellType::HomeLane(Rang::Yellow);
-            }
+    // Only the current colour can move, the rolls & the rules are NOT checked here (see `choose()` & `Sandbox::play()`)
+    // `Position::Locked` as `start` moves a locked goti
+    // Result is `MoveResult::Attacked` if it captured gotis (eg. on an unsafe start square), else `MoveResult::Unlocked` for an unlock
+    fn move_goti(&mut self, colour: Rang, start: Position, dist: u8) -> Result<MoveResult, LudoError> {
+        self.check_turn(colour)?;
 
-            // Blue
-            for c in 9..14 {
-                board[7][c].cell_type = LudoCellType::HomeLane(Rang::Blue);
-            }
+        let dest = self.check_move(colour, start, dist)?;
+
+        let id = self
+            .find_goti(colour, start)
+            .ok_or(LudoError::NoGotiAt(colour, start))?;
+
+        // If it is a SafeSpot, attack can not happen
+        // If it is any other square where two colors can meet,
+        // then presence of any other colour = presence of enemy... (except the partner, see `Rules::teams`)
+        let attacked = dest
+            .square(colour)
+            .filter(|square| !self.is_safe_square(*square) && self.gotis_at(*square).any(|g| self.is_enemy(colour, g.colour)));
+
+        // Mutable changes here; This MUST be an atomic change, either all or none
+        self.gotis[id].position = dest;
+        if dest == Position::Finished && self.is_finished(colour) && !self.finish_order.contains(&colour) {
+            self.finish_order.push(colour);
+        }
+        if let Some(square) = attacked {
+            self.capture_at(colour, square);
         }
 
-        let mut moving_gotis = Map::new();
-        let mut num_finished = Map::new();
-        for colour in active_colours.iter() {
-            let colour = *colour;
-            moving_gotis.insert(colour, Vec::new());
-            num_finished.insert(colour, 0);
+        if attacked.is_some() {
+            Ok(MoveResult::Attacked(dest))
+        } else if dest == Position::Finished {
+            Ok(MoveResult::Finished)
+        } else if start == Position::Locked {
+            Ok(MoveResult::Unlocked)
+        } else {
+            Ok(MoveResult::NormalMove(dest))
         }
+    }
 
-        LudoEngine {
-            curr_colour: *active_colours.first().unwrap(),
-            active_colours,
-            board,
-            locked_gotis,
-            moving_gotis,
-            num_finished,
+    // `colour` can move now
+    fn check_turn(&self, colour: Rang) -> Result<(), LudoError> {
+        if !self.active_colours.contains(&colour) {
+            return Err(LudoError::ColourNotPlaying(colour));
+        }
+        if self.is_game_finished() {
+            return Err(LudoError::GameOver);
         }
+        if colour != self.curr_colour {
+            return Err(LudoError::NotYourTurn(colour));
+        }
+
+        Ok(())
     }
 
-    pub fn get_board(&self) -> &[[Box; 15]; 15] {
-        &self.board
+    // Sends every goti on `square` of an enemy of `colour` back to lock
+    fn capture_at(&mut self, colour: Rang, square: Square) {
+        // TIP2: Can add more logic inside `is_enemy()` for special rules, for eg. here all gotis of different colors are enemies, except partners
+        for i in 0..self.gotis.len() {
+            let goti = self.gotis[i];
+            if goti.position.square(goti.colour) == Some(square) && self.is_enemy(colour, goti.colour) {
+                self.gotis[i].position = Position::Locked;
+                *self.num_captures.get_mut(&colour).unwrap() += 1;
+            }
+        }
     }
 
-    /** @note Will always return `true` for a colour that is not playing */
-    pub(crate) fn is_finished(&self, colour: Rang) -> bool {
-        if self.active_colours.contains(&colour) == false {
-            true
-        } else {
-            self.moving_gotis.get(&colour).unwrap().is_empty()
-                && self.locked_gotis.get(&colour).unwrap().is_empty()
+    // Sends a moving goti of `colour` at `position` back to lock, eg. as a penalty
+    fn send_to_lock(&mut self, colour: Rang, position: Position) -> Result<(), LudoError> {
+        if !self.active_colours.contains(&colour) {
+            return Err(LudoError::ColourNotPlaying(colour));
+        }
+        if !position.is_valid() {
+            return Err(LudoError::NotOnRoute(position));
         }
+
+        let id = self
+            .find_goti(colour, position)
+            .filter(|_| matches!(position, Position::Track(_) | Position::HomeLane(_)))
+            .ok_or(LudoError::NoGotiAt(colour, position))?;
+        self.gotis[id].position = Position::Locked;
+
+        Ok(())
     }
 
-    pub(crate) fn set_current_colour(&mut self, colour: Rang) {
-        self.curr_colour = colour;
+    /**
+     * Note: This does NOT check if goti of such `colour` exists at `start`, for easier debugging or other use by the programmer
+     * @returns Ok(destination), if move possible
+     *          Err(LudoError::MoveNotPossible), otherwise if not possible
+     *          Err(LudoError::NotOnRoute), if no goti can ever be at `start`, eg. `Position::Track(60)`
+     */
+    pub fn is_move_possible(&self, colour: Rang, start: Position, dist: u8) -> Result<Position, LudoError> {
+        self.check_move(colour, start, dist)
     }
 
-    // Returns Err(()), if no locked goti present
-    pub fn unlock_goti(&mut self, colour: Rang) -> Result<(), ()> {
-        let locked_positions = Rang::GetLockedPositions(colour);
+    // Position after the move, errors same as `is_move_possible()`
+    fn check_move(&self, colour: Rang, start: Position, dist: u8) -> Result<Position, LudoError> {
+        if !self.active_colours.contains(&colour) {
+            return Err(LudoError::ColourNotPlaying(colour));
+        }
+        if !start.is_valid() {
+            return Err(LudoError::NotOnRoute(start));
+        }
 
-        // SAFETY: If the attacked_goti was moving, that means atleast 1 locked_positions must be empty, so unwrap() wont panic
-        let i = locked_positions
-            .iter()
-            .position(|coord| {
-                self.board[coord.0 as usize][coord.1 as usize]
-                    .gotis
-                    .is_empty()
-                    == false
-            })
-            .unwrap();
-        let locked_coord = locked_positions[i];
+        self.get_destination(colour, start, dist)
+            .ok_or(LudoError::MoveNotPossible(start, dist))
+    }
 
-        match self.move_goti(colour, locked_coord, 6) {
-            Ok(res) => {
-                debug_assert!(res == MoveResult::Unlocked);
-                Ok(())
-            }
-            Err(_) => Err(()),
+    // Where a goti of `colour` at `start` reaches by moving `dist`, None if it can't
+    // Note: A finished goti never moves again, whatever `Rules::finish` says about rolls past the end
+    pub fn get_destination(&self, colour: Rang, start: Position, dist: u8) -> Option<Position> {
+        if !start.is_valid() || start == Position::Finished {
+            return None;
+        }
+        if start == Position::Locked {
+            let can_unlock = self.rules.unlock_rolls.contains(&dist) && !self.is_blocked(colour, Square::start(colour));
+            return can_unlock.then_some(Position::Track(0));
         }
-    }
 
-    // Invariant: Expects that atleast 1 goti of `colour` is present at `start_coord`
-    pub fn move_goti(
-        &mut self,
-        colour: Rang,
-        start_coords: (u8, u8),
-        dist: u8,
-    ) -> Result<MoveResult, String> {
-        let final_coords = match self.is_move_possible(colour, start_coords, dist) {
-            Some(coord) => coord,
-            None => {
-                return Err("Move not possible".to_string());
-            }
+        let enter_home = self.can_enter_home(colour);
+        let after = |steps: u8| match start.advance(steps, enter_home) {
+            Some(position) => Some(position),
+            None => match self.rules.finish {
+                FinishMode::Exact => None,
+                // Rest of the roll is not needed
+                FinishMode::Overshoot => Some(Position::Finished),
+                // Reached the end with some roll left, rest of it is walked back down the home lane
+                FinishMode::BounceBack => {
+                    let back = steps - start.distance_to_finish();
+                    Some(Position::HomeLane(path::LANE_LEN.saturating_sub(back)))
+                }
+            },
         };
+        let dest = after(dist)?;
+
+        // Only blockades & the home lane rule care about the squares on the way
+        if self.rules.blockades || self.rules.no_passing_in_home {
+            for square in (1..=dist).filter_map(|steps| after(steps)?.square(colour)) {
+                // Can neither pass a blockade, nor land on it
+                if self.is_blocked(colour, square) {
+                    return None;
+                }
 
-        // Invariant: cell.gotis has a goti of `colour`
-        let start_cell_goti_index = {
-            let start_cell = &self.board[start_coords.0 as usize][start_coords.1 as usize];
-
-            match start_cell
-                .gotis
-                .iter()
-                .position(|g| g.borrow().colour == colour)
-            {
-                Some(i) => i,
-                None => {
-                    return Err(format!(
-                        "Goti of colour: {:?} doesn't exist at {:?}",
-                        colour, start_coords
-                    ))
+                // Only gotis of this colour can be in its home lane
+                if self.rules.no_passing_in_home
+                    && matches!(square, Square::HomeLane(..))
+                    && self.gotis_at(square).next().is_some()
+                {
+                    return None;
                 }
             }
-        };
+        }
 
-        let was_attack = {
-            let cell = &self.board[final_coords.0 as usize][final_coords.1 as usize];
+        Some(dest)
+    }
+
+    // Positions of moving gotis of `colour`, in order of their ids, empty for non-playing colours
+    pub fn get_positions(&self, colour: Rang) -> Vec<Position> {
+        self.gotis_of(colour)
+            .map(|g| g.position)
+            .filter(|position| matches!(position, Position::Track(_) | Position::HomeLane(_)))
+            .collect()
+    }
 
-            // If it is a SafeSpot, attack can not happen
-            // If it is any other cell where two colors can meet,
-            // then presence of any other colour = presence of enemy... 
-            (cell.cell_type != LudoCellType::SafeSpot) && (
-                cell.gotis.iter().position(|g|  g.borrow().colour != colour).is_some()
-            )
+    // Colours of gotis that will be captured, if a goti of `colour` reaches `to` (an enemy blockade can't be reached)
+    pub fn get_capturable(&self, colour: Rang, to: Position) -> Vec<Rang> {
+        let square = match to.square(colour) {
+            Some(square) if !self.is_safe_square(square) && !self.is_blocked(colour, square) => square,
+            _ => return vec![],
         };
-        let finished = final_coords == Rang::GetEndCoord(colour);
-        let unlocked = final_coords == Rang::GetStartCoord(colour);
 
-        // Mutable changes here; This MUST be an atomic change, either all or none
-        {
-            // SAFETY: goti_index == None, already handled, so .unwrap() won't panic
-            // Cloned `goti`, ab atleast ek reference h goti ka always
-            let start_cell = &self.board[start_coords.0 as usize][start_coords.1 as usize];
-
-            let goti = start_cell.gotis.get(start_cell_goti_index).unwrap().clone();
-            {
-                goti.borrow_mut().coords = final_coords;
-            }
+        self.gotis_at(square)
+            .map(|g| g.colour)
+            .filter(|c| self.is_enemy(colour, *c))
+            .collect()
+    }
 
-            if unlocked {
-                // Remove goti from locked_gotis (& start_cell), and put in moving gotis (& dest_cell)
-                {
-                    let start_cell =
-                        &mut self.board[start_coords.0 as usize][start_coords.1 as usize];
-                    start_cell.gotis.remove(start_cell_goti_index);
-                }
-                {
-                    let dest_cell =
-                        &mut self.board[final_coords.0 as usize][final_coords.1 as usize];
-                    dest_cell.gotis.push(goti.clone());
-                }
+    // Gotis of `other` can be captured by `colour`, ie. it is neither the same colour nor its partner
+    pub fn is_enemy(&self, colour: Rang, other: Rang) -> bool {
+        other != colour && !(self.rules.teams && other == Rang::GetPartner(colour))
+    }
 
-                let goti_index = self
-                    .locked_gotis
-                    .get(&colour)
-                    .unwrap()
-                    .iter()
-                    .position(|g| g == &goti)
-                    .unwrap();
-                self.locked_gotis
-                    .get_mut(&colour)
-                    .unwrap()
-                    .remove(goti_index);
-
-                self.moving_gotis
-                    .get_mut(&colour)
-                    .unwrap()
-                    .push(goti.clone());
-            } else if finished {
-                // Remove goti from everywhere, moving gotis (& start_cell)
-                let start_cell = &mut self.board[start_coords.0 as usize][start_coords.1 as usize];
-                start_cell.gotis.remove(start_cell_goti_index);
-
-                // SAFETY: all these .unwrap() here assume presence of goti in self.moving_gotis also, which must be the case, else it's critical internal bug
-                let goti_index = self
-                    .moving_gotis
-                    .get(&colour)
-                    .unwrap()
-                    .iter()
-                    .position(|g| g == &goti)
-                    .unwrap();
-                self.moving_gotis
-                    .get_mut(&colour)
-                    .unwrap()
-                    .remove(goti_index);
-
-                *self.num_finished.get_mut(&goti.borrow().colour).unwrap() += 1;
-            } else if was_attack {
-                // Remove goti from start_cell, and put in dest_cell
-                {
-                    let start_cell =
-                        &mut self.board[start_coords.0 as usize][start_coords.1 as usize];
-                    start_cell.gotis.remove(start_cell_goti_index);
-                }
-                {
-                    let dest_cell =
-                        &mut self.board[final_coords.0 as usize][final_coords.1 as usize];
-                    dest_cell.gotis.push(goti.clone());
-                }
+    // Colour whose gotis `colour` moves in its turn, the partner once it has finished (see `Rules::teams`)
+    // None if there is nothing left for it to move
+    pub fn get_playing_colour(&self, colour: Rang) -> Option<Rang> {
+        if !self.is_finished(colour) {
+            return Some(colour);
+        }
 
-                
-                // TIP2: Can add more logic inside the lambda for special rules, for eg. here all gotis of different colors are enemies
-                // AND, Remove all attacked_gotis from dest_cell, and put in locked_gotis
-                loop {
-                    let attacked_goti = {
-                        // SAFETY: `goti_to_remove_idx` is a valid index in `dest_cells.gotis`, so unwraps won't panic
-                        let dest_cell =
-                            &mut self.board[final_coords.0 as usize][final_coords.1 as usize];
-                        let goti_to_remove_idx = dest_cell
-                            .gotis
-                            .iter()
-                            .position(|g| g.borrow().colour != colour);
-
-                        if goti_to_remove_idx.is_none() {
-                            break;
-                        }
-
-                        let goti_ref = dest_cell
-                            .gotis
-                            .get(goti_to_remove_idx.unwrap())
-                            .unwrap()
-                            .clone();
-
-                        dest_cell.gotis.remove(goti_to_remove_idx.unwrap());
-
-                        goti_ref
-                    };
-
-                    let locked_positions = Rang::GetLockedPositions(attacked_goti.borrow().colour);
-
-                    // SAFETY: If the attacked_goti was moving, that means atleast 1 locked_positions must be empty, so unwrap() wont panic
-                    let i = locked_positions
-                        .iter()
-                        .position(|coord| {
-                            self.board[coord.0 as usize][coord.1 as usize]
-                                .gotis
-                                .is_empty()
-                        })
-                        .unwrap();
-                    let empty_locked_cell = &mut self.board[locked_positions[i].0 as usize]
-                        [locked_positions[i].1 as usize];
-
-                    attacked_goti.borrow_mut().coords = locked_positions[i];
-                    empty_locked_cell.gotis.push(attacked_goti);
-                }
-            } else {
-                // Normal move
-                // Remove goti from start_cell, and put in dest_cell
-                {
-                    let start_cell =
-                        &mut self.board[start_coords.0 as usize][start_coords.1 as usize];
-                    start_cell.gotis.remove(start_cell_goti_index);
-                }
-                {
-                    let dest_cell =
-                        &mut self.board[final_coords.0 as usize][final_coords.1 as usize];
-                    dest_cell.gotis.push(goti.clone());
-                }
-            }
+        let partner = Rang::GetPartner(colour);
+        if self.rules.teams && !self.is_finished(partner) {
+            Some(partner)
+        } else {
+            None
+        }
+    }
+
+    // A goti of `colour` at `position` can never be captured (locked & finished ones neither)
+    pub fn is_safe(&self, colour: Rang, position: Position) -> bool {
+        match position.square(colour) {
+            Some(square) => self.is_safe_square(square) || self.blockade_at(square).is_some(),
+            None => true,
         }
+    }
 
-        let start_cell = &self.board[start_coords.0 as usize][start_coords.1 as usize];
+    // Colour of the blockade on the square a goti of `colour` at `position` is on, if blockades are in the rules
+    pub fn get_blockade(&self, colour: Rang, position: Position) -> Option<Rang> {
+        self.blockade_at(position.square(colour)?)
+    }
 
-        if was_attack {
-            Ok(MoveResult::Attacked(final_coords))
-        } else if finished {
-            Ok(MoveResult::Finished)
-        } else if unlocked {
-            Ok(MoveResult::Unlocked)
-        } else {
-            match start_cell.cell_type {
-                LudoCellType::Default | LudoCellType::SafeSpot => {
-                    Ok(MoveResult::NormalMove(final_coords))
-                }
-                LudoCellType::HomeLane(c) => {
-                    if c == colour {
-                        Ok(MoveResult::NormalMove(final_coords))
-                    } else {
-                        panic!(
-                            ".is_move_possible() galat coordinate diya: {:?}, rang: {:?} 🥺!!",
-                            final_coords, colour
-                        )
-                    }
-                }
-                _ => panic!(
-                    ".is_move_possible() galat coordinate diya: {:?}, rang: {:?} 🥺!!",
-                    final_coords, colour
-                ),
-            }
+    fn blockade_at(&self, square: Square) -> Option<Rang> {
+        if !self.rules.blockades || matches!(square, Square::HomeLane(..)) {
+            return None;
         }
+
+        let colours: Vec<Rang> = self.gotis_at(square).map(|g| g.colour).collect();
+        colours
+            .iter()
+            .copied()
+            .find(|c| colours.iter().filter(|other| *other == c).count() >= 2)
     }
 
-    /**
-     * Invariant: start_coord is a valid coordinate for a goti to exist on the board
-     * Note: This does NOT check if goti of such `colour` exists on `start_coord`, for easier debugging or other use by the programmer
-     * @returns Some(final_coords), if move possible
-     *          None, otherwise if not possible
-     */
-    pub fn is_move_possible(
-        &self,
-        colour: Rang,
-        start_coord: (u8, u8),
-        mut dist: u8,
-    ) -> Option<(u8, u8)> {
-        if self.board[start_coord.0 as usize][start_coord.1 as usize].cell_type
-            == LudoCellType::NoUse
-        {
-            panic!("Invalid start coord: {:?}", start_coord)
-        }
-
-        let goti_is_locked = Rang::GetLockedPositions(colour).contains(&start_coord);
-
-        if goti_is_locked {
-            return if dist == 6 {
-                Some(Rang::GetStartCoord(colour))
-            } else {
-                None
-            };
+    // A blockade of an enemy colour is on `square`
+    fn is_blocked(&self, colour: Rang, square: Square) -> bool {
+        matches!(self.blockade_at(square), Some(c) if self.is_enemy(colour, c))
+    }
+
+    // Gotis of other colours can't be captured here, as the rules say (home lanes are always safe)
+    fn is_safe_square(&self, square: Square) -> bool {
+        match self.rules.safe_spots {
+            _ if matches!(square, Square::HomeLane(..)) => true,
+            SafeSpots::All => square.is_start() || square.is_star(),
+            SafeSpots::StartOnly => square.is_start(),
+            SafeSpots::None => false,
         }
+    }
 
-        let mut final_coord = start_coord;
+    // Moving gotis of every colour on `square`
+    fn gotis_at(&self, square: Square) -> impl Iterator<Item = &LudoGoti> {
+        self.gotis
+            .iter()
+            .filter(move |g| g.position.square(g.colour) == Some(square))
+    }
 
-        while dist != 0 {
-            
-            final_coord = LudoEngine::get_next_coord(colour, final_coord);
+    fn gotis_of(&self, colour: Rang) -> impl Iterator<Item = &LudoGoti> {
+        self.gotis.iter().filter(move |g| g.colour == colour)
+    }
 
-            if final_coord.0 > 14
-                || final_coord.1 > 14
-                || ((self.board[final_coord.0 as usize][final_coord.1 as usize].cell_type
-                    == LudoCellType::NoUse)
-                    && (final_coord != Rang::GetEndCoord(colour)))
-            {
-                return None;
-            }
+    // Id of a goti of `colour` at `position`, the first one if many (eg. locked ones)
+    fn find_goti(&self, colour: Rang, position: Position) -> Option<usize> {
+        self.gotis
+            .iter()
+            .position(|g| g.colour == colour && g.position == position)
+    }
+
+    // Gotis sorted by colour & then along the route, same for every way of reaching the position
+    fn get_sorted_gotis(&self) -> Vec<LudoGoti> {
+        let mut gotis = self.gotis.clone();
+        gotis.sort_by_key(|g| (g.colour, g.position));
+        gotis
+    }
+
+    // Game is over when only one player is left unfinished (or none, if it was a single player game)
+    // Note: Game is also over once out of `Rules::rounds`, or after the first one finishes with `Rules::stop_at_first`
+    pub(crate) fn is_game_finished(&self) -> bool {
+        if self.rules.rounds > 0 && self.num_rounds >= self.rules.rounds {
+            return true;
+        }
+        if self.rules.teams {
+            return self.active_colours.iter().any(|c| self.is_team_finished(*c));
+        }
 
-            dist -= 1;
+        let playing = self.active_colours.iter().filter(|c| !self.is_finished(**c)).count();
+        if self.rules.stop_at_first {
+            playing < self.active_colours.len()
+        } else {
+            playing == 0 || (playing == 1 && self.active_colours.len() > 1)
         }
+    }
 
-        Some(final_coord)
+    // Colour has finished, and its partner too with `Rules::teams` (a partner that isn't playing is done)
+    pub(crate) fn is_team_finished(&self, colour: Rang) -> bool {
+        self.is_finished(colour) && (!self.rules.teams || self.is_finished(Rang::GetPartner(colour)))
     }
 
-    // Returns coords of movable gotis
-    // Prevent from leaking internal ds
-    // Note: This does NOT handle the case of UNLOCK, handle it yourself then call engine.unlock_goti()
-    pub fn get_movable_gotis(&self, colour: Rang, dist: u8) -> Vec<(u8, u8)> {
-        let mut start_coords = Vec::new();
-        {
-            let gotis = match self.moving_gotis.get(&colour) {
-                Some(gotis) => gotis,
-                None => return vec![],
-            };
+    /**
+     * Colours that won, empty if the game isn't over yet
+     * The first to finish wins, or with `Rules::teams` the first team to finish both partners (both are returned)
+     * Out of rounds with nobody (no team) finished, the most points win, a team adds up both (see `get_score()`)
+     * @note A tie goes to the one earlier in order of play
+     */
+    pub fn get_winners(&self) -> Vec<Rang> {
+        if !self.is_game_finished() {
+            return Vec::new();
+        }
 
-            for goti in gotis {
-                if self
-                    .is_move_possible(colour, goti.borrow().coords, dist)
-                    .is_some()
-                {
-                    start_coords.push(goti.borrow().coords);
-                }
+        let team = |colour: Rang| -> Vec<Rang> {
+            let partner = Rang::GetPartner(colour);
+            if self.rules.teams && self.active_colours.contains(&partner) {
+                vec![colour, partner]
+            } else {
+                vec![colour]
             }
+        };
+
+        if let Some(colour) = self.finish_order.iter().find(|c| self.is_team_finished(**c)) {
+            return team(*colour);
         }
 
-        start_coords
-    }
-
-    // This may return NoUse coord
-    fn get_next_coord(colour: Rang, coord: (u8, u8)) -> (u8, u8) {
-        // arranged as: (start_coord, next_coord)
-        let turns = [
-            // Outer turns
-            ((0, 6), (0, 7)),
-            ((0, 8), (1, 8)),
-            ((6, 0), (6, 1)),
-            ((6, 14), (7, 14)),
-            ((8, 0), (7, 0)),
-            ((8, 14), (8, 13)),
-            ((14, 6), (13, 6)),
-            ((14, 8), (14, 7)),
-            // Inner turns
-            ((9, 6), (8, 5)),
-            ((6, 5), (5, 6)),
-            ((8, 9), (9, 8)),
-            ((5, 8), (6, 9)),
-        ];
-
-        // Check if on outer or inner corners
-        for (current, next) in turns {
-            if coord == current {
-                return next;
+        let points = |colour: Rang| -> u32 { team(colour).iter().map(|c| self.get_score(*c)).sum() };
+        let mut best = self.active_colours[0];
+        for colour in self.active_colours.iter() {
+            if points(*colour) > points(best) {
+                best = *colour;
             }
         }
+        team(best)
+    }
+
+    // Next colour to play after `colour`, skipping the finished ones (unless they still move for a partner)
+    // Note: returns `colour` itself if every other colour is finished
+    pub fn get_next_colour(&self, colour: Rang) -> Rang {
+        let i = self
+            .active_colours
+            .iter()
+            .position(|c| *c == colour)
+            .unwrap_or(0);
 
-        // Check Home turns
-        {
-            let (current, next) = Rang::GetHomeTurn(colour);
-            if coord == current {
+        for j in 1..=self.active_colours.len() {
+            let next = self.active_colours[(i + j) % self.active_colours.len()];
+            if self.get_playing_colour(next).is_some() {
                 return next;
             }
         }
 
-        // Handling rest cases (can return invalid locations, as in function description)
-        if coord.0 == 6 {
-            return (coord.0, coord.1 + 1);
-        } else if coord.0 == 7 {
-            // ie. (7,0)
-            if coord.1 == 0 {
-                return (coord.0 - 1, coord.1);
-            } else if coord.1 < 6 {
-                return (coord.0, coord.1 + 1);
-            } else if coord.1 == 14 {
-                return (coord.0 + 1, coord.1);
-            } else if coord.1 > 8 {
-                return (coord.0, coord.1 - 1);
-            }
-        } else if coord.0 == 8 {
-            return (coord.0, coord.1 - 1);
-        }
-
-        if coord.1 == 6 {
-            return (coord.0 - 1, coord.1);
-        } else if coord.1 == 7 {
-            // ie. (0,7)
-            if coord.0 == 0 {
-                return (coord.0, coord.1 + 1);
-            } else if coord.0 < 6 {
-                return (coord.0 + 1, coord.1);
-            } else if coord.0 == 14 {
-                return (coord.0, coord.1 - 1);
-            } else if coord.0 > 8 {
-                return (coord.0 - 1, coord.1);
-            }
-        } else if coord.1 == 8 {
-            return (coord.0 + 1, coord.1);
-        }
+        colour
+    }
 
-        panic!("Invalid coordinate: {:?}", coord);
+    pub fn get_finish_order(&self) -> &[Rang] {
+        &self.finish_order
     }
 
-    // If any player isn't finished return false, else true
-    // It is upto the game to exit if for eg. you want it to exit even if one player isn't finished
-    pub(crate) fn is_game_finished(&self) -> bool {
-        for colour in self.active_colours.iter() {
-            if self.is_finished(*colour) == false {
-                return false;
+    pub fn get_num_rounds(&self) -> u32 {
+        self.num_rounds
+    }
+
+    // Points of `colour` for `Rules::rounds`, steps moved by all its gotis (a finished one moved one more than the
+    // home lane) and `Rules::capture_points` for each capture
+    pub fn get_score(&self, colour: Rang) -> u32 {
+        let steps: u32 = self
+            .get_positions(colour)
+            .iter()
+            .map(|position| position.steps() as u32)
+            .sum();
+
+        steps
+            + self.get_num_finished(colour) as u32 * Position::Finished.steps() as u32
+            + self.get_num_captures(colour) * self.rules.capture_points
+    }
+
+    // Same player plays again after a bonus roll (a '6' usually), or as the rules say for capturing, finishing or unlocking a goti
+    // `result` is None, if no goti was moved
+    pub fn is_bonus_turn(&self, roll: u8, result: Option<&MoveResult>) -> bool {
+        let rules = &self.rules;
+
+        rules.bonus_rolls.contains(&roll)
+            || match result {
+                Some(MoveResult::Attacked(_)) => rules.bonus_on_capture,
+                Some(MoveResult::Finished) => rules.bonus_on_finish,
+                Some(MoveResult::Unlocked) => rules.bonus_on_unlock,
+                Some(MoveResult::NormalMove(_)) | None => false,
             }
+    }
+
+    // Steps earned by a move, to be played by some goti later in the same turn (0 if none)
+    pub fn get_bonus_steps(&self, result: Option<&MoveResult>) -> u8 {
+        match result {
+            Some(MoveResult::Attacked(_)) => self.rules.capture_steps,
+            Some(MoveResult::Finished) => self.rules.finish_steps,
+            _ => 0,
         }
+    }
+
+    // Some goti of `colour` is out on the board, other than in its home lane
+    pub fn has_goti_on_track(&self, colour: Rang) -> bool {
+        self.get_positions(colour)
+            .iter()
+            .any(|position| matches!(position, Position::Track(_)))
+    }
+
+    // Note: returns 0 for non-playing colors
+    pub fn get_num_captures(&self, colour: Rang) -> u32 {
+        self.num_captures.get(&colour).copied().unwrap_or(0)
+    }
 
-        true
+    // Gotis of `colour` can turn in to the home lane, ie. not held back by `Rules::capture_to_enter_home`
+    pub fn can_enter_home(&self, colour: Rang) -> bool {
+        !self.rules.capture_to_enter_home || self.get_num_captures(colour) > 0
+    }
+
+    // Note: returns 0 for non-playing colors
+    pub(crate) fn get_num_finished(&self, colour: Rang) -> u8 {
+        self.gotis_of(colour).filter(|g| g.position == Position::Finished).count() as u8
     }
 
     // Note: returns None for non-playing colors
     pub(crate) fn get_num_locked(&self, colour: Rang) -> Option<u8> {
-        match self.locked_gotis.get(&colour) {
-            Some(v) => Some(v.len() as u8),
-            None => None
+        if !self.active_colours.contains(&colour) {
+            return None;
+        }
+
+        Some(self.gotis_of(colour).filter(|g| g.position == Position::Locked).count() as u8)
+    }
+}
+
+impl PartialEq for LudoEngine {
+    fn eq(&self, other: &Self) -> bool {
+        self.get_sorted_gotis() == other.get_sorted_gotis()
+            && self.num_captures == other.num_captures
+            && self.active_colours == other.active_colours
+            && self.curr_player == other.curr_player
+            && self.curr_colour == other.curr_colour
+            && self.num_rounds == other.num_rounds
+            && self.finish_order == other.finish_order
+            && self.rules == other.rules
+    }
+}
+
+impl Eq for LudoEngine {}
+
+// Same fields as `eq()`, eg. for a table of positions already seen in a search
+impl Hash for LudoEngine {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.get_sorted_gotis().hash(state);
+        self.num_captures.hash(state);
+        self.active_colours.hash(state);
+        self.curr_player.hash(state);
+        self.curr_colour.hash(state);
+        self.num_rounds.hash(state);
+        self.finish_order.hash(state);
+        self.rules.hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dice::ThreadDice;
+
+    fn engine_at(notation: &str, rules: Rules) -> LudoEngine {
+        LudoEngine::from_notation(notation, rules, std::boxed::Box::new(ThreadDice)).unwrap()
+    }
+
+    fn hash_of(engine: &LudoEngine) -> u64 {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        engine.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    // Red has finished & moves Yellow's gotis, it isn't the same as Yellow's own turn
+    #[test]
+    fn player_is_part_of_the_position() {
+        let rules = Rules::preset("teams").unwrap();
+        let red = engine_at("R:-/G:L,L,L,L/Y:L,L,L,3/B:L,L,L,L R 4/0/0/0", rules.clone());
+        let yellow = engine_at("R:-/G:L,L,L,L/Y:L,L,L,3/B:L,L,L,L Y 4/0/0/0", rules);
+        assert_eq!(red.get_current_colour(), yellow.get_current_colour());
+
+        assert!(red != yellow);
+        assert_ne!(hash_of(&red), hash_of(&yellow));
+    }
+
+    // Red finished first, but Blue finishing completes Green & Blue before Yellow can
+    #[test]
+    fn first_team_to_finish_wins() {
+        let rules = Rules::preset("teams").unwrap();
+        let dice = std::boxed::Box::new(dice::ScriptedDice::new(vec![1]).unwrap());
+        let mut engine = LudoEngine::from_notation("R:-/G:-/Y:L,L,L,L/B:55 B 4/4/0/3", rules, dice).unwrap();
+        assert!(engine.get_winners().is_empty());
+
+        engine.roll().unwrap();
+        engine.choose(1, &Move::Finish { from: Position::HomeLane(4) }).unwrap();
+
+        assert_eq!(engine.get_turn_state(), TurnState::GameOver);
+        assert_eq!(engine.get_finish_order(), [Rang::Red, Rang::Green, Rang::Blue]);
+        assert_eq!(engine.get_winners(), [Rang::Green, Rang::Blue]);
+    }
+
+    // Red's first goti moves on after the second one is unlocked, ids are the other way round when the notation is read
+    #[test]
+    fn same_position_by_two_routes() {
+        let dice = std::boxed::Box::new(dice::ScriptedDice::new(vec![6, 6, 2]).unwrap());
+        let mut played = LudoEngine::new(vec![Rang::Red, Rang::Blue], Rules::default(), dice).unwrap();
+        for mv in [Move::Unlock(0), Move::Unlock(1), Move::Advance { from: Position::Track(0), to: Position::Track(2) }] {
+            let value = played.roll().unwrap()[0];
+            played.choose(value, &mv).unwrap();
+            played.end_turn().unwrap();
+        }
+
+        let read = engine_at(&played.to_notation(), Rules::default());
+        let restored = LudoEngine::from_state(&played.get_state(), std::boxed::Box::new(ThreadDice)).unwrap();
+        for other in [&read, &restored] {
+            assert!(played == *other);
+            assert_eq!(hash_of(&played), hash_of(other));
+        }
+    }
+
+    // Two Blue gotis on the 6th square from Red's start, & two on Red's start square in the second game
+    #[test]
+    fn blockades() {
+        let rules = Rules {
+            name: "Blockades".to_string(),
+            blockades: true,
+            ..Rules::default()
+        };
+
+        let engine = engine_at("R:L,L,L,3/B:L,L,19,19 R 0/0", rules.clone());
+        assert_eq!(engine.get_blockade(Rang::Red, Position::Track(6)), Some(Rang::Blue));
+        assert!(engine.is_safe(Rang::Blue, Position::Track(19)));
+
+        // Can neither land on it (so no capture either) nor pass it
+        assert_eq!(engine.get_destination(Rang::Red, Position::Track(3), 3), None);
+        assert_eq!(engine.get_destination(Rang::Red, Position::Track(3), 5), None);
+        assert_eq!(engine.get_destination(Rang::Red, Position::Track(3), 2), Some(Position::Track(5)));
+        assert!(engine.legal_moves(Rang::Red, 3).is_empty());
+        assert!(engine.get_capturable(Rang::Red, Position::Track(6)).is_empty());
+
+        // Nor come out of lock on to it
+        let engine = engine_at("R:L,L,L,L/B:L,L,13,13 R 0/0", rules);
+        assert!(!engine.can_unlock(Rang::Red, 6));
+        assert!(engine.legal_moves(Rang::Red, 6).is_empty());
+
+        // Yellow is Red's partner, its blockade is only in the way of Green & Blue
+        let rules = Rules {
+            name: "Team blockades".to_string(),
+            blockades: true,
+            teams: true,
+            ..Rules::default()
+        };
+        let engine = engine_at("R:L,L,L,3/G:L,L,L,41/Y:L,L,32,32 R 0/0/0", rules);
+        assert_eq!(engine.get_blockade(Rang::Red, Position::Track(6)), Some(Rang::Yellow));
+        assert_eq!(engine.get_destination(Rang::Red, Position::Track(3), 3), Some(Position::Track(6)));
+        assert_eq!(engine.get_destination(Rang::Red, Position::Track(3), 5), Some(Position::Track(8)));
+        assert_eq!(engine.get_destination(Rang::Green, Position::Track(41), 5), None);
+        assert_eq!(engine.get_destination(Rang::Green, Position::Track(41), 3), Some(Position::Track(44)));
+    }
+
+    // A roll past `Finished` from the home lane, for every colour so each one's own route is used
+    #[test]
+    fn finish_modes() {
+        let notation = "R:49,53,L/G:49,53,L/Y:49,53,L/B:49,53,L R 1/1/1/1";
+        let rules = |name: &str, finish: FinishMode| Rules {
+            name: name.to_string(),
+            finish,
+            ..Rules::default()
+        };
+
+        let exact = engine_at(notation, rules("Exact finish", FinishMode::Exact));
+        let bounce = engine_at(notation, rules("Bounce back", FinishMode::BounceBack));
+        let overshoot = engine_at(notation, rules("Overshoot", FinishMode::Overshoot));
+
+        let lane = Position::HomeLane(2);
+        for colour in [Rang::Red, Rang::Green, Rang::Yellow, Rang::Blue] {
+            // Exactly to the end, or short of it, is the same in every mode
+            for engine in [&exact, &bounce, &overshoot] {
+                assert_eq!(engine.is_move_possible(colour, lane, 3), Ok(Position::Finished));
+                assert_eq!(engine.is_move_possible(colour, Position::Track(49), 6), Ok(Position::HomeLane(4)));
+            }
+
+            assert_eq!(exact.is_move_possible(colour, lane, 5), Err(LudoError::MoveNotPossible(lane, 5)));
+            assert_eq!(bounce.is_move_possible(colour, lane, 5), Ok(Position::HomeLane(3)));
+            assert_eq!(overshoot.is_move_possible(colour, lane, 5), Ok(Position::Finished));
+
+            // Bounces all the way back to where it started
+            assert_eq!(bounce.is_move_possible(colour, lane, 6), Ok(lane));
+            assert_eq!(overshoot.is_move_possible(colour, lane, 6), Ok(Position::Finished));
+
+            // Already finished, there is nothing to bounce back or overshoot
+            for engine in [&exact, &bounce, &overshoot] {
+                let finished = Position::Finished;
+                assert_eq!(engine.is_move_possible(colour, finished, 3), Err(LudoError::MoveNotPossible(finished, 3)));
+            }
         }
     }
 }
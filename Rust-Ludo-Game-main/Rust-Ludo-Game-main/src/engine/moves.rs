@@ -0,0 +1,193 @@
+use super::{LudoEngine, LudoError, MoveResult, Position, Rang};
+
+// A move as the rules allow it, see `LudoEngine::legal_moves()`
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Move {
+    Unlock(usize), // id of the goti coming out of lock
+    Advance { from: Position, to: Position },
+    // `from` is `Position::Locked` for unlocking on to an unsafe start square with enemy gotis on it
+    Capture { from: Position, to: Position, victims: Vec<Rang> }, // colours of the captured gotis, one entry per goti
+    Finish { from: Position },
+}
+
+impl Move {
+    // Where the moved goti was
+    pub fn from(&self) -> Position {
+        match self {
+            Move::Unlock(_) => Position::Locked,
+            Move::Advance { from, .. } | Move::Capture { from, .. } | Move::Finish { from } => *from,
+        }
+    }
+
+    // Where the moved goti will be
+    pub fn to(&self) -> Position {
+        match self {
+            Move::Unlock(_) => Position::Track(0),
+            Move::Advance { to, .. } | Move::Capture { to, .. } => *to,
+            Move::Finish { .. } => Position::Finished,
+        }
+    }
+}
+
+impl LudoEngine {
+    /**
+     * Moves `colour` can play with `roll`, unlocking first then gotis in order of their ids
+     * Gotis of `colour` on the same square give only one move, they are the same
+     * Only the compulsory ones are left, if the rules have any (see `Rules::must_leave_start` & `Rules::must_capture`)
+     * @note Empty for a colour that isn't playing. Whose turn it is isn't checked, see `apply()` for that
+     */
+    pub fn legal_moves(&self, colour: Rang, roll: u8) -> Vec<Move> {
+        let mut moves = Vec::new();
+
+        if self.can_unlock(colour, roll) {
+            // SAFETY: `can_unlock()` is false without a locked goti
+            let id = self.find_goti(colour, Position::Locked).unwrap();
+            moves.push(match self.get_capturable(colour, Position::Track(0)) {
+                victims if victims.is_empty() => Move::Unlock(id),
+                victims => Move::Capture { from: Position::Locked, to: Position::Track(0), victims },
+            });
+        }
+
+        let mut seen = Vec::new();
+        for from in self.get_positions(colour) {
+            if seen.contains(&from) {
+                continue;
+            }
+            seen.push(from);
+
+            let to = match self.get_destination(colour, from, roll) {
+                Some(to) => to,
+                None => continue,
+            };
+            let victims = self.get_capturable(colour, to);
+
+            moves.push(if to == Position::Finished {
+                Move::Finish { from }
+            } else if !victims.is_empty() {
+                Move::Capture { from, to, victims }
+            } else {
+                Move::Advance { from, to }
+            });
+        }
+
+        let rules = self.get_rules();
+        if rules.must_leave_start && self.get_num_locked(colour).unwrap_or(0) > 0 {
+            if moves.iter().any(|m| m.from() == Position::Track(0)) {
+                moves.retain(|m| m.from() == Position::Track(0));
+            } else if moves.iter().any(|m| m.from() == Position::Locked) {
+                moves.retain(|m| m.from() == Position::Locked);
+            }
+        }
+
+        if rules.must_capture && moves.iter().any(|m| matches!(m, Move::Capture { .. })) {
+            moves.retain(|m| matches!(m, Move::Capture { .. }));
+        }
+
+        moves
+    }
+
+    // Plays `mv`, if it is one of `legal_moves(colour, roll)`
+    // Returns Err(LudoError::IllegalMove) if it is not, after the same checks of the turn as `move_goti()`
+    // Note: Values left to play, bonus turns etc. are not updated, see `choose()` for playing a move in the turn
+    pub(super) fn apply(&mut self, colour: Rang, roll: u8, mv: &Move) -> Result<MoveResult, LudoError> {
+        self.check_turn(colour)?;
+        if !self.legal_moves(colour, roll).contains(mv) {
+            return Err(LudoError::IllegalMove(mv.clone()));
+        }
+
+        self.move_goti(colour, mv.from(), roll)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{dice::ThreadDice, Rules, SafeSpots};
+
+    fn engine_at(notation: &str, rules: Rules) -> LudoEngine {
+        LudoEngine::from_notation(notation, rules, Box::new(ThreadDice)).unwrap()
+    }
+
+    fn mensch_at(notation: &str) -> LudoEngine {
+        engine_at(notation, Rules::preset("mensch").unwrap())
+    }
+
+    // Blue is on Red's start square & two more Blue gotis are 2 steps ahead of a Red goti, nothing is safe
+    #[test]
+    fn moves_and_victims() {
+        let rules = Rules {
+            name: "No safe spots".to_string(),
+            safe_spots: SafeSpots::None,
+            ..Rules::default()
+        };
+        let mut engine = engine_at("R:L,3,10,10/B:L,13,18,18 R 0/0", rules);
+
+        let unlock = Move::Capture { from: Position::Locked, to: Position::Track(0), victims: vec![Rang::Blue] };
+        let capture = Move::Capture { from: Position::Track(3), to: Position::Track(5), victims: vec![Rang::Blue, Rang::Blue] };
+        let advance = Move::Advance { from: Position::Track(10), to: Position::Track(12) };
+
+        // Both gotis on 10 are the same move
+        assert_eq!(engine.legal_moves(Rang::Red, 2), [capture.clone(), advance.clone()]);
+        assert_eq!(engine.legal_moves(Rang::Red, 6)[0], unlock);
+
+        assert_eq!(engine.apply(Rang::Red, 2, &capture), Ok(MoveResult::Attacked(Position::Track(5))));
+        assert_eq!(engine.get_positions(Rang::Blue), [Position::Track(13)]);
+        assert_eq!(engine.get_num_captures(Rang::Red), 2);
+
+        assert_eq!(engine.apply(Rang::Red, 6, &unlock), Ok(MoveResult::Attacked(Position::Track(0))));
+        assert!(engine.get_positions(Rang::Blue).is_empty());
+    }
+
+    #[test]
+    fn illegal_moves() {
+        let mut engine = engine_at("R:L,L,10,54/B:L,L,L,L R 0/0", Rules::default());
+
+        assert_eq!(engine.legal_moves(Rang::Red, 2)[1], Move::Finish { from: Position::HomeLane(3) });
+        assert_eq!(engine.apply(Rang::Red, 2, &Move::Finish { from: Position::HomeLane(3) }), Ok(MoveResult::Finished));
+
+        // Wrong roll, a made up move & not Blue's turn
+        let advance = Move::Advance { from: Position::Track(10), to: Position::Track(12) };
+        assert_eq!(engine.apply(Rang::Red, 3, &advance), Err(LudoError::IllegalMove(advance.clone())));
+        let made_up = Move::Advance { from: Position::Track(10), to: Position::Track(20) };
+        assert_eq!(engine.apply(Rang::Red, 2, &made_up), Err(LudoError::IllegalMove(made_up)));
+        assert_eq!(engine.apply(Rang::Blue, 6, &Move::Unlock(4)), Err(LudoError::NotYourTurn(Rang::Blue)));
+
+        assert_eq!(engine.get_positions(Rang::Red), [Position::Track(10)]);
+    }
+
+    #[test]
+    fn must_leave_start() {
+        // Goti on the start square moves first...
+        let engine = mensch_at("R:L,L,0,10/B:L,L,L,L R 0/0");
+        assert_eq!(engine.legal_moves(Rang::Red, 3), [Move::Advance { from: Position::Track(0), to: Position::Track(3) }]);
+        assert_eq!(engine.legal_moves(Rang::Red, 6), [Move::Advance { from: Position::Track(0), to: Position::Track(6) }]);
+
+        // ...& with none there, a 6 must bring one out
+        let engine = mensch_at("R:L,L,L,10/B:L,L,L,L R 0/0");
+        assert!(matches!(engine.legal_moves(Rang::Red, 6)[..], [Move::Unlock(_)]));
+        assert_eq!(engine.legal_moves(Rang::Red, 2), [Move::Advance { from: Position::Track(10), to: Position::Track(12) }]);
+    }
+
+    // Blue is 2 steps ahead of one Red goti
+    #[test]
+    fn must_capture() {
+        let engine = mensch_at("R:L,L,3,10/B:L,L,L,18 R 0/0");
+        let capture = Move::Capture { from: Position::Track(3), to: Position::Track(5), victims: vec![Rang::Blue] };
+        assert_eq!(engine.legal_moves(Rang::Red, 2), [capture]);
+        assert_eq!(engine.legal_moves(Rang::Red, 1).len(), 2);
+    }
+
+    // Red gotis on the 2nd & 4th squares of its home lane
+    #[test]
+    fn no_passing_in_home() {
+        let engine = mensch_at("R:L,L,52,54/B:L,L,L,L R 0/0");
+        assert_eq!(engine.get_destination(Rang::Red, Position::HomeLane(1), 1), Some(Position::HomeLane(2)));
+        assert_eq!(engine.get_destination(Rang::Red, Position::HomeLane(1), 2), None);
+        assert_eq!(engine.get_destination(Rang::Red, Position::HomeLane(1), 3), None);
+        assert_eq!(engine.get_destination(Rang::Red, Position::HomeLane(3), 2), Some(Position::Finished));
+
+        // Same jump without the rule
+        let engine = engine_at("R:L,L,52,54/B:L,L,L,L R 0/0", Rules::default());
+        assert_eq!(engine.get_destination(Rang::Red, Position::HomeLane(1), 3), Some(Position::HomeLane(4)));
+    }
+}
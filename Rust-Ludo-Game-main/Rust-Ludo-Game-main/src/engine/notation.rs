@@ -0,0 +1,345 @@
+// One line notation for a position, similar to FEN in chess. Eg.
+//
+//      R:L,L,0,23/Y:L,L,L,L/B:L,51,55 B 0/0/1
+//
+// Space separated fields:
+//  1. Gotis of each playing colour, in order of play, separated by '/'
+//     `<colour>:<goti>,<goti>...`, colour is one of R, G, Y, B
+//     each goti is `L` if locked, else the number of steps it has moved from its start square
+//     (0 = start square, 50 = just before turning in to the home lane, 51-55 = home lane,
+//      56 = square just behind the start square, only reached by going round again, see `Rules::capture_to_enter_home`)
+//     `-` if the colour has no gotis left on board
+//  2. Colour whose turn it is (if it has finished, it moves for its partner, see `Rules::teams`)
+//  3. Number of finished gotis of each colour, in same order as field 1, separated by '/'
+//  4. Number of gotis captured by each colour, same as field 3 (only if the rules need it, see
+//     `Rules::capture_to_enter_home` & `Rules::capture_points`, or before field 5)
+//  5. Number of rounds played (only with `Rules::rounds`)
+// Rules are not part of the notation, the number of gotis must match the rules it is used with
+
+use std::collections::BTreeMap as Map;
+
+use super::{
+    dice::Dice,
+    path::{HOME_TURN, TRACK_LEN},
+    turn::Turn,
+    EngineState, LudoEngine, LudoError, Position, Rang, Rules,
+};
+
+// Steps of the square just behind start square, it is not on the usual path
+const BEHIND_START: u8 = 56;
+
+impl LudoEngine {
+    // Position of a goti that has moved `steps` from its start square, as written in the notation
+    pub fn get_position_at_steps(steps: u8) -> Option<Position> {
+        match steps {
+            BEHIND_START => Some(Position::Track(TRACK_LEN - 1)),
+            _ => match Position::Track(0).advance(steps, true)? {
+                Position::Finished => None,
+                position => Some(position),
+            },
+        }
+    }
+
+    // Inverse of `get_position_at_steps()`, None if `position` is not a moving one
+    pub fn get_steps_to(position: Position) -> Option<u8> {
+        match position {
+            Position::Locked | Position::Finished => None,
+            _ if !position.is_valid() => None,
+            Position::Track(steps) if steps > HOME_TURN => Some(BEHIND_START),
+            position => Some(position.steps()),
+        }
+    }
+
+    pub fn to_notation(&self) -> String {
+        let state = self.get_state();
+
+        let gotis: Vec<String> = state
+            .active_colours
+            .iter()
+            .map(|colour| {
+                let mut gotis = vec!["L".to_string(); state.num_locked[colour] as usize];
+                // SAFETY: Moving gotis are always on the track or in the home lane
+                let mut moving: Vec<u8> = state.moving_gotis[colour]
+                    .iter()
+                    .map(|position| LudoEngine::get_steps_to(*position).unwrap())
+                    .collect();
+                moving.sort_unstable();
+                gotis.extend(moving.iter().map(|steps| steps.to_string()));
+
+                if gotis.is_empty() {
+                    gotis.push("-".to_string());
+                }
+
+                format!("{}:{}", Rang::GetLetter(*colour), gotis.join(","))
+            })
+            .collect();
+
+        let finished: Vec<String> = state
+            .active_colours
+            .iter()
+            .map(|colour| state.num_finished[colour].to_string())
+            .collect();
+
+        let mut notation = format!(
+            "{} {} {}",
+            gotis.join("/"),
+            Rang::GetLetter(state.curr_player),
+            finished.join("/")
+        );
+
+        let rounds = state.rules.rounds > 0;
+        if state.rules.capture_to_enter_home || state.rules.capture_points > 0 || rounds {
+            let captures: Vec<String> = state
+                .active_colours
+                .iter()
+                .map(|colour| state.num_captures[colour].to_string())
+                .collect();
+            notation.push(' ');
+            notation.push_str(&captures.join("/"));
+        }
+        if rounds {
+            notation.push_str(&format!(" {}", state.num_rounds));
+        }
+
+        notation
+    }
+
+    pub fn from_notation(notation: &str, rules: Rules, dice: Box<dyn Dice>) -> Result<Self, LudoError> {
+        let state = LudoEngine::parse_notation(notation, rules).map_err(LudoError::InvalidPosition)?;
+
+        // Counts & side to move are checked here
+        let mut engine = LudoEngine::from_state(&state, dice)?;
+
+        // Order they finished in isn't in the notation, taken as order of play
+        let finished: Vec<Rang> = engine.active_colours.iter().copied().filter(|c| engine.is_finished(*c)).collect();
+        engine.finish_order = finished;
+
+        // A position is always between turns, the player whose turn it is rolls next
+        engine.start_turn_of(engine.curr_player);
+
+        Ok(engine)
+    }
+
+    fn parse_notation(notation: &str, rules: Rules) -> Result<EngineState, String> {
+        let fields: Vec<&str> = notation.split_whitespace().collect();
+        if !(3..=5).contains(&fields.len()) {
+            return Err(format!("Expected 3 to 5 fields, found {}", fields.len()));
+        }
+
+        let mut active_colours = Vec::new();
+        let mut moving_gotis = Map::new();
+        let mut num_locked = Map::new();
+        let mut num_finished = Map::new();
+
+        for block in fields[0].split('/') {
+            let (colour, gotis) = block
+                .split_once(':')
+                .ok_or_else(|| format!("Expected `<colour>:<gotis>`, found {:?}", block))?;
+            let colour = LudoEngine::parse_colour(colour)?;
+
+            let gotis: Vec<&str> = if gotis == "-" { Vec::new() } else { gotis.split(',').collect() };
+            // Finished ones are added up later, this keeps the counts from overflowing
+            if gotis.len() > rules.gotis_per_colour as usize {
+                return Err(format!(
+                    "{:?} has {} gotis on board, only {} in the rules",
+                    colour,
+                    gotis.len(),
+                    rules.gotis_per_colour
+                ));
+            }
+
+            let mut locked = 0;
+            let mut moving = Vec::new();
+            for goti in gotis {
+                if goti == "L" {
+                    locked += 1;
+                    continue;
+                }
+
+                let position = goti
+                    .parse::<u8>()
+                    .ok()
+                    .and_then(LudoEngine::get_position_at_steps)
+                    .ok_or_else(|| format!("Invalid goti {:?} for {:?}", goti, colour))?;
+                moving.push(position);
+            }
+
+            active_colours.push(colour);
+            moving_gotis.insert(colour, moving);
+            num_locked.insert(colour, locked);
+        }
+
+        // Counts of each colour, separated by '/'
+        let counts = |field: &str, what: &str| -> Result<Vec<u32>, String> {
+            let counts: Vec<&str> = field.split('/').collect();
+            if counts.len() != active_colours.len() {
+                return Err(format!(
+                    "Expected {} count of {} colours, found {}",
+                    what,
+                    active_colours.len(),
+                    counts.len()
+                ));
+            }
+
+            counts
+                .iter()
+                .map(|count| count.parse::<u32>().map_err(|_| format!("Invalid {} count: {:?}", what, count)))
+                .collect()
+        };
+
+        for (colour, count) in active_colours.iter().zip(counts(fields[2], "finished")?) {
+            let count = u8::try_from(count).map_err(|_| format!("Invalid finished count: {:?}", count))?;
+            num_finished.insert(*colour, count);
+        }
+
+        let mut num_captures = Map::new();
+        if let Some(field) = fields.get(3) {
+            num_captures = active_colours.iter().copied().zip(counts(field, "captured")?).collect();
+        }
+
+        let num_rounds = match fields.get(4) {
+            Some(field) => field.parse::<u32>().map_err(|_| format!("Invalid round count: {:?}", field))?,
+            None => 0,
+        };
+
+        let curr_player = LudoEngine::parse_colour(fields[1])?;
+
+        Ok(EngineState {
+            curr_player,
+            curr_colour: curr_player,
+            turn: Turn::default(),
+            active_colours,
+            moving_gotis,
+            num_locked,
+            num_finished,
+            num_captures,
+            seed: None,
+            num_rolls: 0,
+            num_rounds,
+            finish_order: Vec::new(),
+            rules,
+        })
+    }
+
+    fn parse_colour(letter: &str) -> Result<Rang, String> {
+        let mut chars = letter.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Rang::FromLetter(c).map_err(|e| e.to_string()),
+            _ => Err(format!("Unknown colour: {:?}", letter)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{
+        dice::{SeededDice, ThreadDice},
+        TurnState,
+    };
+
+    fn round_trip(notation: &str, rules: Rules) -> String {
+        LudoEngine::from_notation(notation, rules, Box::new(ThreadDice))
+            .unwrap()
+            .to_notation()
+    }
+
+    #[test]
+    fn notation_round_trip() {
+        let standard = Rules::default();
+        for notation in [
+            "R:L,L,0,23/Y:L,L,L,L/B:L,51,55 B 0/0/1",
+            "R:L,L,L,L/G:L,L,L,L/Y:L,L,L,L/B:L,L,L,L R 0/0/0/0",
+            "G:4,4,17,50/B:L,L,L,L G 0/0",
+        ] {
+            assert_eq!(round_trip(notation, standard.clone()), notation);
+        }
+
+        let teams = Rules::preset("teams").unwrap();
+        let notation = "R:-/G:L,L,L,L/Y:L,L,L,3/B:55 B 4/0/0/3";
+        assert_eq!(round_trip(notation, teams), notation);
+
+        let kill = Rules {
+            name: "Kill to enter".to_string(),
+            capture_to_enter_home: true,
+            ..Rules::default()
+        };
+        let notation = "R:L,L,7,56/Y:L,52 Y 0/2 0/1";
+        assert_eq!(round_trip(notation, kill), notation);
+
+        // Points are scored for captures, and the round decides when the game is over
+        let score = Rules::preset("score").unwrap();
+        let notation = "R:L,L,7,20/G:L,L,L,L/B:L,L,L,30 G 0/0/0 2/0/1 12";
+        assert_eq!(round_trip(notation, score.clone()), notation);
+        assert_eq!(round_trip("R:L,L,L,L/B:L,L,L,L R 0/0", score), "R:L,L,L,L/B:L,L,L,L R 0/0 0/0 0");
+    }
+
+    #[test]
+    fn rounds_and_captures_are_read() {
+        let read = |notation: &str| {
+            LudoEngine::from_notation(notation, Rules::preset("score").unwrap(), Box::new(ThreadDice)).unwrap()
+        };
+
+        let engine = read("R:L,L,7,20/B:L,L,L,30 B 0/0 2/1 29");
+        assert_eq!(engine.get_num_rounds(), 29);
+        assert_eq!(engine.get_num_captures(Rang::Red), 2);
+        assert_eq!(engine.get_score(Rang::Red), 7 + 20 + 2 * 20);
+        assert_eq!(engine.get_turn_state(), TurnState::AwaitingRoll);
+
+        // Out of rounds
+        let engine = read("R:L,L,7,20/B:L,L,L,30 B 0/0 2/1 30");
+        assert_eq!(engine.get_turn_state(), TurnState::GameOver);
+    }
+
+    // Same gotis after writing a played position out & reading it back
+    #[test]
+    fn played_position_round_trip() {
+        let colours = vec![Rang::Red, Rang::Green, Rang::Blue];
+        let mut engine = LudoEngine::new(colours.clone(), Rules::default(), Box::new(SeededDice::new(7))).unwrap();
+
+        // Last of the moves each time, so gotis spread out
+        for _ in 0..200 {
+            if engine.get_turn_state() == TurnState::GameOver {
+                break;
+            }
+
+            engine.roll().unwrap();
+            while engine.get_turn_state() == TurnState::AwaitingMove {
+                match engine.get_plays().pop() {
+                    Some((value, mv)) => {
+                        engine.choose(value, &mv).unwrap();
+                    }
+                    None => engine.pass().unwrap(),
+                }
+            }
+            if engine.get_turn_state() == TurnState::TurnOver {
+                engine.end_turn().unwrap();
+            }
+        }
+
+        let notation = engine.to_notation();
+        let read = LudoEngine::from_notation(&notation, Rules::default(), Box::new(ThreadDice)).unwrap();
+        assert_eq!(read.to_notation(), notation);
+        assert_eq!(read.current_player(), engine.current_player());
+        // Ids of the gotis aren't in the notation
+        let sorted = |engine: &LudoEngine, colour: Rang| {
+            let mut positions = engine.get_positions(colour);
+            positions.sort();
+            positions
+        };
+        for colour in colours {
+            assert_eq!(sorted(&read, colour), sorted(&engine, colour));
+            assert_eq!(read.get_num_finished(colour), engine.get_num_finished(colour));
+        }
+    }
+
+    #[test]
+    fn too_many_gotis() {
+        let notation = format!("R:{}/B:L,L,L,L R 0/0", vec!["L"; 300].join(","));
+        assert!(LudoEngine::from_notation(&notation, Rules::default(), Box::new(ThreadDice)).is_err());
+
+        // Right count on board, but some finished too
+        let notation = "R:L,L,L,L/B:L,L,L,L R 1/0";
+        assert!(LudoEngine::from_notation(notation, Rules::default(), Box::new(ThreadDice)).is_err());
+    }
+}
@@ -0,0 +1,250 @@
+use serde::{Deserialize, Serialize};
+
+use super::Rang;
+
+// Squares round the board, starting at the start square of Red and going the way gotis move
+pub(super) const TRACK_LEN: u8 = 52;
+const TRACK: [(u8, u8); TRACK_LEN as usize] = [
+    (13, 6), (12, 6), (11, 6), (10, 6), (9, 6), (8, 5), (8, 4), (8, 3), (8, 2), (8, 1), (8, 0), (7, 0), (6, 0),
+    (6, 1), (6, 2), (6, 3), (6, 4), (6, 5), (5, 6), (4, 6), (3, 6), (2, 6), (1, 6), (0, 6), (0, 7), (0, 8),
+    (1, 8), (2, 8), (3, 8), (4, 8), (5, 8), (6, 9), (6, 10), (6, 11), (6, 12), (6, 13), (6, 14), (7, 14), (8, 14),
+    (8, 13), (8, 12), (8, 11), (8, 10), (8, 9), (9, 8), (10, 8), (11, 8), (12, 8), (13, 8), (14, 8), (14, 7), (14, 6),
+];
+
+// Squares of the home lane, from the home turn to the end
+pub(super) const LANE_LEN: u8 = 5;
+// Track square a goti turns in to the home lane from, counted from its start square
+pub(super) const HOME_TURN: u8 = 50;
+
+// Index in `TRACK` of (13,6), (6,1), (1,8), (8,13)... the start squares
+const fn start_index(colour: Rang) -> u8 {
+    match colour {
+        Rang::Red => 0,
+        Rang::Green => 13,
+        Rang::Yellow => 26,
+        Rang::Blue => 39,
+    }
+}
+
+const fn home_lane(colour: Rang) -> [(u8, u8); LANE_LEN as usize] {
+    match colour {
+        Rang::Red => [(13, 7), (12, 7), (11, 7), (10, 7), (9, 7)],
+        Rang::Green => [(7, 1), (7, 2), (7, 3), (7, 4), (7, 5)],
+        Rang::Yellow => [(1, 7), (2, 7), (3, 7), (4, 7), (5, 7)],
+        Rang::Blue => [(7, 13), (7, 12), (7, 11), (7, 10), (7, 9)],
+    }
+}
+
+// Inverse of `TRACK`, u8::MAX where there is no track square
+const TRACK_INDEX: [[u8; 15]; 15] = {
+    let mut index = [[u8::MAX; 15]; 15];
+    let mut i = 0;
+    while i < TRACK.len() {
+        index[TRACK[i].0 as usize][TRACK[i].1 as usize] = i as u8;
+        i += 1;
+    }
+    index
+};
+
+// Where a goti is along the route of its colour, the board grid is only needed to draw it
+// Ordered along the route, ie. locked first & finished last
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Position {
+    Locked,
+    Track(u8),    // squares moved from the start square, 0..=51 (51 is just behind the start square, reached only by going round)
+    HomeLane(u8), // 0..=4, from the home turn
+    Finished,
+}
+
+// A square as it is on the board, the same track square is a different `Position` for each colour
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum Square {
+    Track(u8), // index in `TRACK`
+    HomeLane(Rang, u8),
+}
+
+impl Position {
+    // Steps moved from the start square, eg. for how far ahead a goti is
+    // A finished goti is one step past the last square of the home lane, a locked one is at 0 same as on the start square
+    // None if it is not on the route, eg. `Track(60)`
+    pub fn progress(self) -> Option<u8> {
+        self.is_valid().then(|| self.steps())
+    }
+
+    // Same as `progress()`, for a position known to be valid
+    pub(super) fn steps(self) -> u8 {
+        match self {
+            Position::Locked => 0,
+            Position::Track(steps) => steps,
+            Position::HomeLane(i) => HOME_TURN + 1 + i,
+            Position::Finished => HOME_TURN + 1 + LANE_LEN,
+        }
+    }
+
+    // Steps still to go to finish, going in to the home lane at the home turn
+    // Note: From just behind the start square it is a whole round again
+    pub(super) fn distance_to_finish(self) -> u8 {
+        match self {
+            Position::Track(steps) if steps > HOME_TURN => TRACK_LEN - steps + Position::Track(0).distance_to_finish(),
+            Position::Locked => Position::Track(0).distance_to_finish(),
+            _ => Position::Finished.steps() - self.steps(),
+        }
+    }
+
+    // Position after moving `dist` steps ahead, None if that goes past `Finished`
+    // Goes on round the board at the home turn, if not `enter_home` (a goti already in the home lane goes on in it)
+    pub(super) fn advance(self, dist: u8, enter_home: bool) -> Option<Position> {
+        match self {
+            _ if dist == 0 => Some(self),
+            Position::Locked | Position::Finished => None,
+            Position::Track(steps) if !enter_home => Some(Position::Track(((steps as u32 + dist as u32) % TRACK_LEN as u32) as u8)),
+            // Just behind the start square, the route starts again from the next step
+            Position::Track(steps) if steps > HOME_TURN => Position::Track(0).advance(dist - 1, enter_home),
+            _ => match self.steps() as u32 + dist as u32 {
+                steps if steps <= HOME_TURN as u32 => Some(Position::Track(steps as u8)),
+                steps if steps < Position::Finished.steps() as u32 => Some(Position::HomeLane(steps as u8 - HOME_TURN - 1)),
+                steps if steps == Position::Finished.steps() as u32 => Some(Position::Finished),
+                _ => None,
+            },
+        }
+    }
+
+    // Track & home lane positions are in range, eg. not `Track(60)`
+    pub(super) fn is_valid(self) -> bool {
+        match self {
+            Position::Track(steps) => steps < TRACK_LEN,
+            Position::HomeLane(i) => i < LANE_LEN,
+            Position::Locked | Position::Finished => true,
+        }
+    }
+
+    pub(super) fn square(self, colour: Rang) -> Option<Square> {
+        if !self.is_valid() {
+            return None;
+        }
+
+        match self {
+            Position::Track(steps) => Some(Square::Track((start_index(colour) + steps) % TRACK_LEN)),
+            Position::HomeLane(i) => Some(Square::HomeLane(colour, i)),
+            Position::Locked | Position::Finished => None,
+        }
+    }
+
+    // Board coordinate, only for drawing
+    // None if `Locked`, see `Rang::GetLockedPositions()` for where locked gotis are drawn
+    pub fn to_coord(self, colour: Rang) -> Option<(u8, u8)> {
+        match self {
+            Position::Finished => Some(Rang::GetEndCoord(colour)),
+            _ => self.square(colour).map(Square::coord),
+        }
+    }
+
+    // A goti of `colour` here & one of `other_colour` at `other` are on the same square of the board
+    // Note: Never for locked or finished gotis, they are not on a square
+    pub fn same_square(self, colour: Rang, other: Position, other_colour: Rang) -> bool {
+        matches!(self.square(colour), Some(square) if other.square(other_colour) == Some(square))
+    }
+}
+
+impl Square {
+    // None if `coord` is neither on the track nor in a home lane
+    pub(super) fn from_coord(coord: (u8, u8)) -> Option<Square> {
+        if coord.0 > 14 || coord.1 > 14 {
+            return None;
+        }
+
+        match TRACK_INDEX[coord.0 as usize][coord.1 as usize] {
+            u8::MAX => [Rang::Red, Rang::Green, Rang::Yellow, Rang::Blue].into_iter().find_map(|colour| {
+                let i = home_lane(colour).iter().position(|c| *c == coord)?;
+                Some(Square::HomeLane(colour, i as u8))
+            }),
+            i => Some(Square::Track(i)),
+        }
+    }
+
+    pub(super) fn coord(self) -> (u8, u8) {
+        match self {
+            Square::Track(i) => TRACK[i as usize],
+            Square::HomeLane(colour, i) => home_lane(colour)[i as usize],
+        }
+    }
+
+    // Start square of `colour`, where its gotis come out of lock
+    pub(super) fn start(colour: Rang) -> Square {
+        Square::Track(start_index(colour))
+    }
+
+    pub(super) fn is_start(self) -> bool {
+        matches!(self, Square::Track(i) if i % 13 == 0)
+    }
+
+    // The star square 8 steps after each start square
+    pub(super) fn is_star(self) -> bool {
+        matches!(self, Square::Track(i) if i % 13 == 8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Same squares as the board had before positions, when gotis were moved by their coords
+    #[test]
+    fn route_of_every_colour() {
+        // Start square, home turn & first square of the home lane, last square of the home lane, end
+        let routes = [
+            (Rang::Red, (13, 6), (14, 7), (13, 7), (9, 7), (8, 7)),
+            (Rang::Green, (6, 1), (7, 0), (7, 1), (7, 5), (7, 6)),
+            (Rang::Yellow, (1, 8), (0, 7), (1, 7), (5, 7), (6, 7)),
+            (Rang::Blue, (8, 13), (7, 14), (7, 13), (7, 9), (7, 8)),
+        ];
+
+        for (colour, start, home_turn, lane_start, lane_end, end) in routes {
+            assert_eq!(Square::start(colour).coord(), start);
+            assert_eq!(Position::Track(0).to_coord(colour), Some(start));
+            assert_eq!(Position::Track(HOME_TURN).to_coord(colour), Some(home_turn));
+            assert_eq!(Position::HomeLane(0).to_coord(colour), Some(lane_start));
+            assert_eq!(Position::HomeLane(LANE_LEN - 1).to_coord(colour), Some(lane_end));
+            assert_eq!(Position::Finished.to_coord(colour), Some(end));
+
+            // Home lane is a straight line from the home turn to the end
+            let lane: Vec<(u8, u8)> = (0..LANE_LEN).filter_map(|i| Position::HomeLane(i).to_coord(colour)).collect();
+            for (a, b) in [home_turn].iter().chain(lane.iter()).zip(lane.iter().chain([end].iter())) {
+                assert_eq!(a.0.abs_diff(b.0) + a.1.abs_diff(b.1), 1, "{:?} lane {:?} to {:?}", colour, a, b);
+            }
+            for (i, coord) in lane.iter().enumerate() {
+                assert_eq!(Square::from_coord(*coord), Some(Square::HomeLane(colour, i as u8)));
+            }
+        }
+
+        // Start & star squares
+        let mut safe: Vec<(u8, u8)> = (0..TRACK_LEN)
+            .map(Square::Track)
+            .filter(|square| square.is_start() || square.is_star())
+            .map(Square::coord)
+            .collect();
+        safe.sort();
+        assert_eq!(safe, [(1, 8), (2, 6), (6, 1), (6, 12), (8, 2), (8, 13), (12, 8), (13, 6)]);
+
+        // Every track square is on the board once, & is found back from its coord
+        for i in 0..TRACK_LEN {
+            let coord = Square::Track(i).coord();
+            assert_eq!(Square::from_coord(coord), Some(Square::Track(i)));
+        }
+    }
+
+    // Positions that can't be on the route, eg. from a hand edited save
+    #[test]
+    fn off_the_route() {
+        for position in [Position::Track(TRACK_LEN), Position::Track(u8::MAX), Position::HomeLane(LANE_LEN), Position::HomeLane(250)] {
+            assert_eq!(position.progress(), None);
+            assert_eq!(position.square(Rang::Red), None);
+            assert_eq!(position.to_coord(Rang::Red), None);
+            assert!(!position.same_square(Rang::Red, position, Rang::Red));
+        }
+
+        assert_eq!(Position::Locked.progress(), Some(0));
+        assert_eq!(Position::HomeLane(4).progress(), Some(55));
+        assert_eq!(Position::Finished.progress(), Some(56));
+    }
+}
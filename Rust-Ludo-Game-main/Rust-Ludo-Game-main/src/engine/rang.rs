@@ -1,4 +1,8 @@
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+use serde::{Deserialize, Serialize};
+
+use super::LudoError;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum Rang {
     Red,    
     Green,  
@@ -8,15 +12,6 @@ pub enum Rang {
 
 #[allow(non_snake_case)]
 impl Rang {
-    pub(crate) fn GetStartCoord(colour: Rang) -> (u8, u8) {
-        match colour {
-            Self::Red => (13,6),
-            Self::Green => (6,1),
-            Self::Yellow => (1,8),
-            Self::Blue => (8,13),
-        }
-    }
-
     pub(crate) fn GetEndCoord(colour: Rang) -> (u8, u8) {
         match colour {
             Self::Red => (8,7),
@@ -26,12 +21,33 @@ impl Rang {
         }
     }
 
-    pub fn GetHomeTurn(colour: Rang) -> ((u8,u8),(u8,u8)) {
+    // Single letter used in position notation & game records
+    pub fn GetLetter(colour: Rang) -> char {
+        match colour {
+            Self::Red => 'R',
+            Self::Green => 'G',
+            Self::Yellow => 'Y',
+            Self::Blue => 'B',
+        }
+    }
+
+    pub fn FromLetter(letter: char) -> Result<Rang, LudoError> {
+        match letter {
+            'R' => Ok(Self::Red),
+            'G' => Ok(Self::Green),
+            'Y' => Ok(Self::Yellow),
+            'B' => Ok(Self::Blue),
+            _ => Err(LudoError::UnknownColour(letter)),
+        }
+    }
+
+    // Sitting opposite, partners in a game with `Rules::teams`
+    pub fn GetPartner(colour: Rang) -> Rang {
         match colour {
-            Self::Red => ((14,7), (13,7)),
-            Self::Green => ((7,0), (7,1)),
-            Self::Yellow => ((0,7), (1,7)),
-            Self::Blue => ((7,14), (7,13))
+            Self::Red => Self::Yellow,
+            Self::Green => Self::Blue,
+            Self::Yellow => Self::Red,
+            Self::Blue => Self::Green,
         }
     }
 
@@ -0,0 +1,256 @@
+// House rules, loaded from a JSON file (or one of the presets, see `Rules::preset()`). Keys left out keep their standard value, eg.
+//
+//      {
+//          "name": "Office 3rd floor",
+//          "unlock_rolls": [1, 6],
+//          "safe_spots": "StartOnly",
+//          "gotis_per_colour": 2
+//      }
+//
+// Keys:
+//  name             - shown in game records, "Standard" is only for the standard rules
+//  unlock_rolls     - rolls that can bring a goti out of lock
+//  bonus_rolls      - rolls that give another turn
+//  bonus_on_capture - another turn for capturing a goti
+//  bonus_on_finish  - another turn for finishing a goti
+//  bonus_on_unlock  - another turn for unlocking a goti (even if the roll itself doesn't give one)
+//  safe_spots       - "All" (the 8 star squares), "StartOnly" (only the 4 start squares) or "None"
+//  gotis_per_colour - 1 to 4
+//  finish           - "Exact" (need exact roll to finish), "BounceBack" (rest of a roll going past the end is
+//                     walked back down the home lane) or "Overshoot" (any roll reaching the end finishes)
+//  three_sixes      - third '6' in a row forfeits the turn: "Skip" (it's just not played), "Revert" (moves
+//                     of the whole turn are taken back), "SendBack" (goti moved last in the turn goes back to lock),
+//                     or "None" (no such rule)
+//  blockades        - 2 or more gotis of a colour on a square (other than home lane) can't be passed or captured
+//                     by other colours
+//  capture_to_enter_home - gotis of a colour go round the board again instead of entering home lane, till that
+//                     colour has captured a goti
+//  dice             - number of dice rolled in a turn, 1 or 2. Each dice is played on its own, by the same or
+//                     different gotis
+//  unlock_on_sum    - both dice adding up to one of `unlock_rolls` can bring a goti out of lock (using both)
+//  bonus_on_doubles - another turn for rolling doubles
+//  capture_steps    - steps to move some goti by, for capturing a goti (0 for none)
+//  finish_steps     - steps to move some goti by, for finishing a goti (0 for none)
+//  stuck_rolls      - rolls in a row a colour gets, while it has no goti on the track (eg. all locked) to move
+//  must_leave_start - unlocking is compulsory, and a goti on its start square must move on first while others
+//                     of its colour are still locked
+//  must_capture     - a capture is compulsory, if one is possible
+//  no_passing_in_home - gotis can't pass or land on others of their colour in the home lane
+//  gotis_to_win     - gotis a colour has to finish (0 for all of them)
+//  stop_at_first    - game is over as soon as a colour finishes, instead of playing on till only one is left
+//  rounds           - game is over after these many rounds (0 for no limit), then whoever has most points wins: steps
+//                     moved by its gotis, plus `capture_points` for every goti it captured
+//  capture_points   - points for a capture, in a game with `rounds`
+//  teams            - Red & Yellow play against Green & Blue. Partners don't capture each other and can share
+//                     squares, one who has finished moves the gotis of the partner, team wins when both finish
+
+use std::{fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SafeSpots {
+    All,
+    StartOnly,
+    None,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FinishMode {
+    Exact,
+    BounceBack,
+    Overshoot,
+}
+
+// Penalty for rolling three '6's in a row
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SixesPenalty {
+    None,
+    Skip,
+    Revert,
+    SendBack,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Rules {
+    pub name: String,
+    pub unlock_rolls: Vec<u8>,
+    pub bonus_rolls: Vec<u8>,
+    pub bonus_on_capture: bool,
+    pub bonus_on_finish: bool,
+    pub bonus_on_unlock: bool,
+    pub safe_spots: SafeSpots,
+    pub gotis_per_colour: u8,
+    pub finish: FinishMode,
+    pub three_sixes: SixesPenalty,
+    pub blockades: bool,
+    pub capture_to_enter_home: bool,
+    pub dice: u8,
+    pub unlock_on_sum: bool,
+    pub bonus_on_doubles: bool,
+    pub capture_steps: u8,
+    pub finish_steps: u8,
+    pub stuck_rolls: u8,
+    pub must_leave_start: bool,
+    pub must_capture: bool,
+    pub no_passing_in_home: bool,
+    pub gotis_to_win: u8,
+    pub stop_at_first: bool,
+    pub rounds: u32,
+    pub capture_points: u32,
+    pub teams: bool,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            name: "Standard".to_string(),
+            unlock_rolls: vec![6],
+            bonus_rolls: vec![6],
+            bonus_on_capture: true,
+            bonus_on_finish: true,
+            bonus_on_unlock: false, // unlocking needs a 6 anyway
+            safe_spots: SafeSpots::All,
+            gotis_per_colour: 4,
+            finish: FinishMode::Exact,
+            three_sixes: SixesPenalty::None,
+            blockades: false,
+            capture_to_enter_home: false,
+            dice: 1,
+            unlock_on_sum: false,
+            bonus_on_doubles: false,
+            capture_steps: 0,
+            finish_steps: 0,
+            stuck_rolls: 1,
+            must_leave_start: false,
+            must_capture: false,
+            no_passing_in_home: false,
+            gotis_to_win: 0,
+            stop_at_first: false,
+            rounds: 0,
+            capture_points: 0,
+            teams: false,
+        }
+    }
+}
+
+impl Rules {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=4).contains(&self.gotis_per_colour) {
+            return Err(format!("gotis_per_colour must be 1 to 4, found {}", self.gotis_per_colour));
+        }
+        if self.unlock_rolls.is_empty() {
+            return Err("unlock_rolls can't be empty, gotis would never leave lock".to_string());
+        }
+        for roll in self.unlock_rolls.iter().chain(self.bonus_rolls.iter()) {
+            if !(1..=6).contains(roll) {
+                return Err(format!("Not a roll of the dice: {}", roll));
+            }
+        }
+        if !(1..=2).contains(&self.dice) {
+            return Err(format!("dice must be 1 or 2, found {}", self.dice));
+        }
+        if self.gotis_to_win > self.gotis_per_colour {
+            return Err(format!(
+                "gotis_to_win ({}) can't be more than gotis_per_colour ({})",
+                self.gotis_to_win, self.gotis_per_colour
+            ));
+        }
+        if !(1..=6).contains(&self.stuck_rolls) {
+            return Err(format!("stuck_rolls must be 1 to 6, found {}", self.stuck_rolls));
+        }
+        if self.dice > 1 && self.three_sixes != SixesPenalty::None {
+            return Err("three_sixes is only for a single dice".to_string());
+        }
+        if self.name == "Standard" && *self != Rules::default() {
+            return Err("Give your house rules a name other than \"Standard\"".to_string());
+        }
+
+        Ok(())
+    }
+
+    // Well known variants, that can be given by name instead of a file
+    pub fn preset(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "standard" => Some(Rules::default()),
+            // Two dice, enter on a 5, doubles play again, 20 steps for a capture & 10 for reaching home
+            "parcheesi" => Some(Rules {
+                name: "Parcheesi".to_string(),
+                unlock_rolls: vec![5],
+                bonus_rolls: vec![],
+                bonus_on_capture: false,
+                bonus_on_finish: false,
+                blockades: true,
+                dice: 2,
+                unlock_on_sum: true,
+                bonus_on_doubles: true,
+                capture_steps: 20,
+                finish_steps: 10,
+                ..Rules::default()
+            }),
+            // No safe squares, 3 tries to roll a 6 with nothing out, clear the start square & capture when possible
+            "mensch" | "mensch ärgere dich nicht" => Some(Rules {
+                name: "Mensch ärgere dich nicht".to_string(),
+                bonus_on_capture: false,
+                bonus_on_finish: false,
+                safe_spots: SafeSpots::None,
+                stuck_rolls: 3,
+                must_leave_start: true,
+                must_capture: true,
+                no_passing_in_home: true,
+                ..Rules::default()
+            }),
+            // First to finish a single goti wins
+            "quick" => Some(Rules {
+                name: "Quick".to_string(),
+                gotis_to_win: 1,
+                stop_at_first: true,
+                ..Rules::default()
+            }),
+            // 30 rounds, most points wins
+            "score" => Some(Rules {
+                name: "Score".to_string(),
+                rounds: 30,
+                capture_points: 20,
+                ..Rules::default()
+            }),
+            // 2 vs 2
+            "teams" => Some(Rules {
+                name: "Teams".to_string(),
+                teams: true,
+                ..Rules::default()
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let json = fs::read_to_string(path).map_err(|e| format!("Couldn't read {:?}: {}", path, e))?;
+        let rules: Rules = serde_json::from_str(&json).map_err(|e| format!("Invalid rules in {:?}: {}", path, e))?;
+
+        rules.validate()?;
+        Ok(rules)
+    }
+
+    // Value of the `Rules` tag in game records, just the name for standard rules
+    pub fn to_tag(&self) -> String {
+        if *self == Rules::default() {
+            self.name.clone()
+        } else {
+            // SAFETY: Nothing in `Rules` can fail to serialize
+            serde_json::to_string(self).unwrap()
+        }
+    }
+
+    pub fn from_tag(tag: &str) -> Result<Self, String> {
+        if tag == "Standard" {
+            return Ok(Rules::default());
+        }
+
+        let rules: Rules = serde_json::from_str(tag).map_err(|e| format!("Invalid rules {:?}: {}", tag, e))?;
+        rules.validate()?;
+
+        Ok(rules)
+    }
+}
@@ -0,0 +1,60 @@
+use std::ops::Deref;
+
+use super::{LudoEngine, LudoError, Move, MoveResult, Rang};
+
+// Copy of an engine for searching ahead (eg. bots), any colour can play any roll on it
+// Note: The turn isn't followed here, so the copy can't be turned back in to a `LudoEngine` & played on with
+#[derive(Clone)]
+pub struct Sandbox {
+    engine: LudoEngine,
+}
+
+impl LudoEngine {
+    pub fn sandbox(&self) -> Sandbox {
+        Sandbox { engine: self.clone() }
+    }
+}
+
+impl Sandbox {
+    // Plays `mv`, if it is one of `legal_moves(colour, roll)`, same as it would be in the turn of `colour`
+    // Note: Values left, bonus turns etc. are left to the caller, see `LudoEngine::is_bonus_turn()`
+    pub fn play(&mut self, colour: Rang, roll: u8, mv: &Move) -> Result<MoveResult, LudoError> {
+        if !self.engine.active_colours.contains(&colour) {
+            return Err(LudoError::ColourNotPlaying(colour));
+        }
+
+        self.engine.curr_colour = colour;
+        self.engine.apply(colour, roll, mv)
+    }
+}
+
+// Everything that only looks at the position
+impl Deref for Sandbox {
+    type Target = LudoEngine;
+
+    fn deref(&self) -> &LudoEngine {
+        &self.engine
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{dice::ThreadDice, Position, Rules, TurnState};
+
+    // Blue plays in Red's turn, only on the copy
+    #[test]
+    fn plays_on_a_copy() {
+        let engine = LudoEngine::from_notation("R:L,L,L,3/B:L,L,L,10 R 0/0", Rules::default(), Box::new(ThreadDice)).unwrap();
+
+        let mut sandbox = engine.sandbox();
+        let mv = Move::Advance { from: Position::Track(10), to: Position::Track(14) };
+        assert_eq!(sandbox.play(Rang::Blue, 4, &mv), Ok(MoveResult::NormalMove(Position::Track(14))));
+        assert_eq!(sandbox.play(Rang::Blue, 4, &mv), Err(LudoError::IllegalMove(mv)));
+        assert_eq!(sandbox.play(Rang::Green, 4, &Move::Unlock(0)), Err(LudoError::ColourNotPlaying(Rang::Green)));
+
+        assert_eq!(sandbox.get_positions(Rang::Blue), [Position::Track(14)]);
+        assert_eq!(engine.get_positions(Rang::Blue), [Position::Track(10)]);
+        assert_eq!(engine.get_turn_state(), TurnState::AwaitingRoll);
+    }
+}
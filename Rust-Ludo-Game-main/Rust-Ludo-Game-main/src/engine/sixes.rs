@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+
+use super::{EngineState, LudoEngine, LudoError, MoveResult, Position, Rang, SixesPenalty};
+
+// Keeps count of '6's in a row in the current turn, for the three sixes rule (see `Rules::three_sixes`)
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConsecutiveSixes {
+    sixes: u8,
+    turn_start: Option<Box<EngineState>>, // position before the first roll of the turn, to revert to
+    last_moved: Option<Position>,         // where the goti moved last in this turn is now
+}
+
+impl ConsecutiveSixes {
+    /**
+     * Call with every roll, before it is played. `bonus_turn` is false for the first roll of a turn
+     * @returns Ok(true) if the roll forfeits the turn, the penalty is already applied to `engine` then
+     *          Err if the penalty can't be applied, eg. the goti moved last isn't where it was left
+     * @note Not kept in `engine` while this runs, else the position to revert to would hold one more inside it
+     */
+    pub(super) fn check_roll(
+        &mut self,
+        engine: &mut LudoEngine,
+        colour: Rang,
+        roll: u8,
+        bonus_turn: bool,
+    ) -> Result<bool, LudoError> {
+        let penalty = engine.get_rules().three_sixes;
+
+        if !bonus_turn {
+            *self = ConsecutiveSixes::default();
+            if penalty == SixesPenalty::Revert {
+                self.turn_start = Some(Box::new(engine.get_state()));
+            }
+        }
+
+        // Not counted without the rule, a long run of '6's would overflow
+        if roll != 6 || penalty == SixesPenalty::None {
+            self.sixes = 0;
+            return Ok(false);
+        }
+
+        self.sixes += 1;
+        if self.sixes < 3 {
+            return Ok(false);
+        }
+
+        let applied = match penalty {
+            SixesPenalty::None | SixesPenalty::Skip => Ok(()),
+            SixesPenalty::Revert => match &self.turn_start {
+                Some(state) => engine.restore_state(state),
+                None => Ok(()),
+            },
+            // Nothing to send back if it finished, or nothing was moved
+            SixesPenalty::SendBack => match self.last_moved {
+                Some(position) => engine.send_to_lock(colour, position),
+                None => Ok(()),
+            },
+        };
+
+        // Counting starts again either way
+        *self = ConsecutiveSixes::default();
+        applied.map(|_| true)
+    }
+
+    // Makes sure a saved count can be played on with `engine`, so `check_roll()` works on it
+    pub(super) fn check(&self, engine: &LudoEngine) -> Result<(), String> {
+        if self.sixes >= 3 {
+            return Err(format!("{} sixes in a row, the turn should be over", self.sixes));
+        }
+        if let Some(position) = self.last_moved {
+            let colour = engine.get_current_colour();
+            if !engine.get_positions(colour).contains(&position) {
+                return Err(format!("{:?} goti moved last is not at {:?}", colour, position));
+            }
+        }
+        if let Some(state) = &self.turn_start {
+            LudoEngine::build_from_state(state).map_err(|e| format!("Start of turn: {}", e))?;
+        }
+
+        Ok(())
+    }
+
+    // Call after a goti of `colour` moved
+    pub(super) fn played(&mut self, result: &MoveResult) {
+        match result {
+            MoveResult::NormalMove(position) | MoveResult::Attacked(position) => self.last_moved = Some(*position),
+            MoveResult::Unlocked => self.last_moved = Some(Position::Track(0)),
+            MoveResult::Finished => self.last_moved = None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::{dice::ScriptedDice, LudoEngine, LudoError, Position, Rang, Rules, SixesPenalty};
+
+    // Goti moved last is moved again outside of the turn (only the engine itself can), the third '6' has nothing to send back
+    #[test]
+    fn penalty_that_cant_be_applied_is_an_error() {
+        let rules = Rules {
+            name: "Send back".to_string(),
+            three_sixes: SixesPenalty::SendBack,
+            gotis_per_colour: 1,
+            ..Rules::default()
+        };
+        let dice = Box::new(ScriptedDice::new(vec![6]).unwrap());
+        let mut engine = LudoEngine::new(vec![Rang::Red, Rang::Blue], rules, dice).unwrap();
+
+        for _ in 0..2 {
+            engine.roll().unwrap();
+            let (value, mv) = engine.get_plays()[0].clone();
+            engine.choose(value, &mv).unwrap();
+            engine.end_turn().unwrap();
+        }
+        engine.move_goti(Rang::Red, Position::Track(6), 1).unwrap();
+
+        assert_eq!(engine.roll(), Err(LudoError::NoGotiAt(Rang::Red, Position::Track(6))));
+    }
+}
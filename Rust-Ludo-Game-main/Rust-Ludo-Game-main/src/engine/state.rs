@@ -0,0 +1,190 @@
+use std::collections::BTreeMap as Map;
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    dice::{Dice, ThreadDice},
+    turn::Turn,
+    LudoEngine, LudoError, Position, Rang, Rules,
+};
+
+// Copy of everything inside `LudoEngine` (but the dice), in the form written in save files
+// `LudoEngine::from_state()` builds the engine again
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EngineState {
+    pub active_colours: Vec<Rang>,
+    pub curr_player: Rang, // whose turn it is
+    pub curr_colour: Rang, // colour it moves
+    pub turn: Turn,        // where the turn is, see `TurnState`
+    pub moving_gotis: Map<Rang, Vec<Position>>, // positions of each moving goti
+    pub num_locked: Map<Rang, u8>,
+    pub num_finished: Map<Rang, u8>,
+    pub num_captures: Map<Rang, u32>, // gotis captured by each colour
+
+    pub seed: Option<u64>, // seed of the dice used, if any
+    pub num_rolls: u64,    // rolls done till now
+    pub num_rounds: u32,   // rounds played till now, see `LudoEngine::end_turn()`
+    pub finish_order: Vec<Rang>, // colours in the order they finished
+    pub rules: Rules,
+}
+
+impl LudoEngine {
+    pub fn get_state(&self) -> EngineState {
+        EngineState {
+            active_colours: self.active_colours.clone(),
+            curr_player: self.curr_player,
+            curr_colour: self.curr_colour,
+            turn: self.turn.clone(),
+            moving_gotis: self
+                .active_colours
+                .iter()
+                .map(|colour| (*colour, self.get_positions(*colour)))
+                .collect(),
+            num_locked: self
+                .active_colours
+                .iter()
+                .map(|colour| (*colour, self.get_num_locked(*colour).unwrap_or(0)))
+                .collect(),
+            num_finished: self
+                .active_colours
+                .iter()
+                .map(|colour| (*colour, self.get_num_finished(*colour)))
+                .collect(),
+            num_captures: self.num_captures.clone(),
+            seed: self.dice.seed(),
+            num_rolls: self.num_rolls,
+            num_rounds: self.num_rounds,
+            finish_order: self.finish_order.clone(),
+            rules: self.rules.clone(),
+        }
+    }
+
+    /**
+     * Rebuilds the engine from a saved state
+     * @note If `dice` has the same seed as was recorded, the rolls already done are skipped, so the game continues with the same rolls
+     */
+    pub fn from_state(state: &EngineState, mut dice: Box<dyn Dice>) -> Result<Self, LudoError> {
+        let mut engine = LudoEngine::build_from_state(state)?;
+
+        if dice.seed().is_some() && dice.seed() == state.seed {
+            for _ in 0..state.num_rolls {
+                dice.roll();
+            }
+        }
+        engine.dice = dice;
+
+        Ok(engine)
+    }
+
+    // Puts every goti back as it was in `state`, eg. for undo
+    // Note: Dice is kept as it is, ie. rolls are NOT rewinded
+    pub fn restore_state(&mut self, state: &EngineState) -> Result<(), LudoError> {
+        let mut engine = LudoEngine::build_from_state(state)?;
+
+        std::mem::swap(&mut engine.dice, &mut self.dice);
+        engine.num_rolls = self.num_rolls;
+        *self = engine;
+
+        Ok(())
+    }
+
+    // Dice of the returned engine is just a placeholder
+    pub(super) fn build_from_state(state: &EngineState) -> Result<Self, LudoError> {
+        let invalid = LudoError::InvalidPosition;
+
+        // Start with all gotis locked, then move them where they were
+        // Colours & rules are checked here
+        let mut engine = LudoEngine::new(state.active_colours.clone(), state.rules.clone(), Box::new(ThreadDice))?;
+        for colour in [state.curr_player, state.curr_colour] {
+            if !state.active_colours.contains(&colour) {
+                return Err(invalid(format!("Current colour {:?} is not playing", colour)));
+            }
+        }
+        let num_gotis = state.rules.gotis_per_colour as usize;
+
+        engine.curr_player = state.curr_player;
+        engine.curr_colour = state.curr_colour;
+        engine.num_rolls = state.num_rolls;
+        engine.num_rounds = state.num_rounds;
+
+        for (i, colour) in state.finish_order.iter().enumerate() {
+            if !state.active_colours.contains(colour) || state.finish_order[..i].contains(colour) {
+                return Err(invalid(format!("Invalid finishing order {:?}", state.finish_order)));
+            }
+        }
+        engine.finish_order = state.finish_order.clone();
+
+        for colour in state.active_colours.iter() {
+            let colour = *colour;
+            let empty = Vec::new();
+            let moving = state.moving_gotis.get(&colour).unwrap_or(&empty);
+            let num_locked = *state.num_locked.get(&colour).unwrap_or(&0);
+            let num_finished = *state.num_finished.get(&colour).unwrap_or(&0);
+
+            if moving.len() + num_locked as usize + num_finished as usize != num_gotis {
+                return Err(invalid(format!(
+                    "{:?} should have {} gotis, found {} moving, {} locked & {} finished",
+                    colour,
+                    num_gotis,
+                    moving.len(),
+                    num_locked,
+                    num_finished
+                )));
+            }
+
+            for position in moving.iter() {
+                if !position.is_valid() || !matches!(position, Position::Track(_) | Position::HomeLane(_)) {
+                    return Err(invalid(format!("{:?} goti can not be moving at {:?}", colour, position)));
+                }
+
+                engine.take_locked_goti(colour, *position);
+            }
+
+            for _ in 0..num_finished {
+                engine.take_locked_goti(colour, Position::Finished);
+            }
+            engine
+                .num_captures
+                .insert(colour, *state.num_captures.get(&colour).unwrap_or(&0));
+        }
+
+        // Checked against the gotis, eg. the one moved last must be where the turn says
+        state.turn.check(&engine).map_err(invalid)?;
+        engine.turn = state.turn.clone();
+
+        Ok(engine)
+    }
+
+    // Puts a locked goti of `colour` at `position`
+    // Invariant: Only for building an engine, atleast 1 goti of `colour` must be locked
+    fn take_locked_goti(&mut self, colour: Rang, position: Position) {
+        let id = self.find_goti(colour, Position::Locked).unwrap();
+        self.gotis[id].position = position;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{dice::ScriptedDice, SixesPenalty};
+
+    // Hand edited save, the turn says a goti moved last where there is none
+    #[test]
+    fn turn_not_matching_gotis_is_rejected() {
+        let rules = Rules {
+            name: "Send back".to_string(),
+            three_sixes: SixesPenalty::SendBack,
+            ..Rules::default()
+        };
+        let engine = LudoEngine::new(vec![Rang::Red, Rang::Blue], rules, Box::new(ThreadDice)).unwrap();
+
+        let mut json = serde_json::to_value(engine.get_state()).unwrap();
+        json["turn"]["bonus_turn"] = true.into();
+        json["turn"]["sixes"]["sixes"] = 2.into();
+        json["turn"]["sixes"]["last_moved"] = serde_json::json!({ "Track": 5 });
+        let state: EngineState = serde_json::from_value(json).unwrap();
+
+        let dice = Box::new(ScriptedDice::new(vec![6]).unwrap());
+        assert!(matches!(LudoEngine::from_state(&state, dice), Err(LudoError::InvalidPosition(_))));
+    }
+}
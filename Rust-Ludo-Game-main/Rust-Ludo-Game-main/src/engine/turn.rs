@@ -0,0 +1,351 @@
+use serde::{Deserialize, Serialize};
+
+use super::{sixes::ConsecutiveSixes, LudoEngine, LudoError, Move, MoveResult, Position, Rang};
+
+// Where the current turn is, every front end (and bot) plays through these same steps
+//
+//      AwaitingRoll --roll()--> AwaitingMove --choose()/pass()--> TurnOver --end_turn()--> AwaitingRoll
+//
+// `choose()` stays in `AwaitingMove` while there is more of the roll to play, and a third '6' in a row goes straight
+// to `TurnOver` (see `Rules::three_sixes`). `GameOver` is reached from any of them, as soon as the game is over
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TurnState {
+    #[default]
+    AwaitingRoll,
+    AwaitingMove,
+    TurnOver,
+    GameOver,
+}
+
+// Values still to be played in the current turn. With one dice that is just the roll, with two (see `Rules::dice`)
+// each dice moves a goti on its own, and steps earned for a capture or finish are added as they come
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Turn {
+    state: TurnState,
+    rolled: Vec<u8>,   // dice rolled at the start of the turn
+    left: Vec<u8>,     // values not played yet, dice first then bonus steps
+    bonus: bool,       // same player rolls again once this turn is over
+    bonus_turn: bool,  // this turn is itself a bonus turn
+    stuck: u8,         // rolls in a row the player couldn't move with nothing on the track, see `Rules::stuck_rolls`
+    sixes: ConsecutiveSixes,
+}
+
+impl Turn {
+    // Makes sure a saved turn can be played on with `engine`, eg. one read from a save file that was edited by hand
+    pub(super) fn check(&self, engine: &LudoEngine) -> Result<(), String> {
+        let rules = engine.get_rules();
+
+        if !self.rolled.is_empty()
+            && (self.rolled.len() != rules.dice as usize || self.rolled.iter().any(|d| !(1..=6).contains(d)))
+        {
+            return Err(format!("Not a roll of the dice: {:?}", self.rolled));
+        }
+        if self.stuck >= rules.stuck_rolls {
+            return Err(format!("Stuck for {} rolls, only {} are given", self.stuck, rules.stuck_rolls));
+        }
+
+        self.sixes.check(engine)
+    }
+}
+
+impl LudoEngine {
+    pub fn get_turn_state(&self) -> TurnState {
+        self.turn.state
+    }
+
+    // Player whose turn it is, it moves the gotis of `get_current_colour()`
+    pub fn current_player(&self) -> Rang {
+        self.curr_player
+    }
+
+    // Values not played yet in this turn
+    pub fn get_left(&self) -> &[u8] {
+        &self.turn.left
+    }
+
+    // Rolls the dice for the current player
+    // Returns Err, if the three sixes penalty can't be applied (see `ConsecutiveSixes::check_roll()`)
+    pub fn roll(&mut self) -> Result<Vec<u8>, LudoError> {
+        self.check_state(TurnState::AwaitingRoll)?;
+
+        let dice: Vec<u8> = (0..self.rules.dice).map(|_| self.roll_once()).collect();
+        self.start_turn(dice.clone())?;
+
+        Ok(dice)
+    }
+
+    // Same as `roll()` with dice rolled elsewhere, eg. read from a game record or the same roll again after an undo
+    pub fn play_roll(&mut self, dice: &[u8]) -> Result<(), LudoError> {
+        self.check_state(TurnState::AwaitingRoll)?;
+        if dice.len() != self.rules.dice as usize || dice.iter().any(|d| !(1..=6).contains(d)) {
+            return Err(LudoError::InvalidRoll(dice.to_vec()));
+        }
+
+        self.start_turn(dice.to_vec())
+    }
+
+    /**
+     * Every (value, move) that can be played now, in order of the values left
+     * Moves of one value are in the same order as `legal_moves()`, with the dice added up for `Rules::unlock_on_sum` at last
+     * @note Empty if not `TurnState::AwaitingMove`
+     */
+    pub fn get_plays(&self) -> Vec<(u8, Move)> {
+        if self.turn.state != TurnState::AwaitingMove {
+            return Vec::new();
+        }
+
+        let colour = self.curr_colour;
+        let left = &self.turn.left;
+        let mut plays = Vec::new();
+
+        for (i, value) in left.iter().enumerate() {
+            // Doubles give the same moves twice
+            if left[..i].contains(value) {
+                continue;
+            }
+
+            plays.extend(self.legal_moves(colour, *value).into_iter().map(|mv| (*value, mv)));
+        }
+
+        // Only with both dice still unplayed
+        if self.rules.unlock_on_sum && self.turn.rolled.len() == 2 && *left == self.turn.rolled {
+            let sum = left.iter().sum();
+            if let Some(mv) = self.legal_moves(colour, sum).into_iter().find(|mv| mv.from() == Position::Locked) {
+                plays.push((sum, mv));
+            }
+        }
+
+        plays
+    }
+
+    // Plays `mv` with `value`, which must be one of `get_plays()`
+    pub fn choose(&mut self, value: u8, mv: &Move) -> Result<MoveResult, LudoError> {
+        self.check_state(TurnState::AwaitingMove)?;
+        if !self.get_plays().contains(&(value, mv.clone())) {
+            return Err(LudoError::IllegalMove(mv.clone()));
+        }
+
+        let colour = self.curr_colour;
+        let result = self.apply(colour, value, mv)?;
+        self.turn.sixes.played(&result);
+        self.turn.stuck = 0;
+
+        match self.turn.left.iter().position(|v| *v == value) {
+            Some(i) => {
+                self.turn.left.remove(i);
+            }
+            // Dice added up, can't be one of them alone as no dice is 0
+            None => self.turn.left.clear(),
+        }
+
+        if self.is_bonus_turn(value, Some(&result)) {
+            self.turn.bonus = true;
+        }
+
+        let steps = self.get_bonus_steps(Some(&result));
+        if steps > 0 {
+            self.turn.left.push(steps);
+        }
+
+        // Last goti finished with steps left to play, they can't be used (nor go to the partner)
+        if self.is_finished(colour) {
+            self.turn.left.clear();
+        }
+
+        self.after_play();
+        Ok(result)
+    }
+
+    /**
+     * Rest of the roll can't be played, it still gives a bonus turn if it would have with a move
+     * Returns Err(LudoError::MustMove) if something in `get_plays()` can be played
+     */
+    pub fn pass(&mut self) -> Result<(), LudoError> {
+        self.check_state(TurnState::AwaitingMove)?;
+        if !self.get_plays().is_empty() {
+            return Err(LudoError::MustMove);
+        }
+
+        if self.turn.left.iter().any(|value| self.is_bonus_turn(*value, None)) {
+            self.turn.bonus = true;
+        }
+
+        // Eg. all locked, gets a few tries to roll one that unlocks
+        if !self.has_goti_on_track(self.curr_colour) {
+            self.turn.stuck += 1;
+            if self.turn.stuck < self.rules.stuck_rolls {
+                self.turn.bonus = true;
+            } else {
+                self.turn.stuck = 0;
+            }
+        }
+
+        self.turn.left.clear();
+        self.after_play();
+        Ok(())
+    }
+
+    // Dice goes to the next player, or back to the same one for a bonus turn
+    // Rounds are counted here, a round is over when the dice goes back to an earlier player
+    pub fn end_turn(&mut self) -> Result<(), LudoError> {
+        self.check_state(TurnState::TurnOver)?;
+
+        let player = self.curr_player;
+        // Finished, a partner left to move for plays in its own turn
+        let bonus = self.turn.bonus && !self.is_finished(self.curr_colour);
+        let next = if bonus { player } else { self.get_next_colour(player) };
+
+        let position = |c: Rang| self.active_colours.iter().position(|a| *a == c);
+        if !bonus && position(next) <= position(player) {
+            self.num_rounds += 1;
+        }
+
+        self.turn = Turn {
+            bonus_turn: bonus,
+            stuck: if bonus { self.turn.stuck } else { 0 },
+            // Only a bonus turn goes on counting '6's
+            sixes: if bonus { std::mem::take(&mut self.turn.sixes) } else { ConsecutiveSixes::default() },
+            ..Turn::default()
+        };
+        self.start_turn_of(next);
+
+        Ok(())
+    }
+
+    // Turn of `player` starts, or of the next one with something left to move
+    pub(super) fn start_turn_of(&mut self, player: Rang) {
+        let player = match self.get_playing_colour(player) {
+            Some(_) => player,
+            None => self.get_next_colour(player),
+        };
+
+        self.curr_player = player;
+        self.curr_colour = self.get_playing_colour(player).unwrap_or(player);
+        self.turn.state = if self.is_game_finished() { TurnState::GameOver } else { TurnState::AwaitingRoll };
+    }
+
+    fn start_turn(&mut self, dice: Vec<u8>) -> Result<(), LudoError> {
+        // Taken out while it runs, see `ConsecutiveSixes::check_roll()`
+        let mut sixes = std::mem::take(&mut self.turn.sixes);
+        let forfeited = sixes.check_roll(self, self.curr_colour, dice[0], self.turn.bonus_turn);
+        self.turn.sixes = sixes;
+        let forfeited = forfeited?;
+
+        let doubles = dice.len() > 1 && dice.iter().all(|d| *d == dice[0]);
+        self.turn.rolled = dice.clone();
+
+        if forfeited {
+            self.turn.left.clear();
+            self.turn.bonus = false;
+            self.turn.state = TurnState::TurnOver;
+        } else {
+            self.turn.left = dice;
+            self.turn.bonus = doubles && self.rules.bonus_on_doubles;
+            self.turn.state = TurnState::AwaitingMove;
+        }
+
+        Ok(())
+    }
+
+    fn after_play(&mut self) {
+        if self.is_game_finished() {
+            self.turn.state = TurnState::GameOver;
+        } else if self.turn.left.is_empty() {
+            self.turn.state = TurnState::TurnOver;
+        }
+    }
+
+    fn check_state(&self, expected: TurnState) -> Result<(), LudoError> {
+        match self.turn.state {
+            state if state == expected => Ok(()),
+            TurnState::GameOver => Err(LudoError::GameOver),
+            state => Err(LudoError::WrongTurnState(state)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{dice::ScriptedDice, Rules};
+
+    fn parcheesi_at(notation: &str, rolls: Vec<u8>) -> LudoEngine {
+        let dice = Box::new(ScriptedDice::new(rolls).unwrap());
+        LudoEngine::from_notation(notation, Rules::preset("parcheesi").unwrap(), dice).unwrap()
+    }
+
+    // Neither dice is a 5, but they add up to one
+    #[test]
+    fn unlock_on_sum() {
+        let mut engine = parcheesi_at("R:L,L,L,L/B:L,L,L,L R 0/0", vec![2, 3]);
+
+        assert_eq!(engine.roll().unwrap(), [2, 3]);
+        assert_eq!(engine.get_plays(), [(5, Move::Unlock(0))]);
+
+        engine.choose(5, &Move::Unlock(0)).unwrap();
+        assert_eq!(engine.get_positions(Rang::Red), [Position::Track(0)]);
+        assert!(engine.get_left().is_empty());
+        assert_eq!(engine.get_turn_state(), TurnState::TurnOver);
+    }
+
+    // Blue is 2 steps ahead of Red's goti, which captures with one dice & gets 20 steps to play after the other
+    #[test]
+    fn bonus_steps() {
+        let mut engine = parcheesi_at("R:L,L,3,44/B:L,L,L,18 R 0/0", vec![2, 4]);
+        engine.roll().unwrap();
+
+        let capture = Move::Capture { from: Position::Track(3), to: Position::Track(5), victims: vec![Rang::Blue] };
+        assert_eq!(engine.choose(2, &capture), Ok(MoveResult::Attacked(Position::Track(5))));
+        assert_eq!(engine.get_left(), [4, 20]);
+
+        engine.choose(4, &Move::Advance { from: Position::Track(44), to: Position::Track(48) }).unwrap();
+        engine.choose(20, &Move::Advance { from: Position::Track(5), to: Position::Track(25) }).unwrap();
+        assert_eq!(engine.get_turn_state(), TurnState::TurnOver);
+
+        // 2 to finish from the home lane, 10 more to play
+        let mut engine = parcheesi_at("R:L,L,3,54/B:L,L,L,L R 0/0", vec![2, 1]);
+        engine.roll().unwrap();
+        assert_eq!(engine.choose(2, &Move::Finish { from: Position::HomeLane(3) }), Ok(MoveResult::Finished));
+        assert_eq!(engine.get_left(), [1, 10]);
+    }
+
+    // Nothing out, 3 tries to roll a 6 before the dice goes on
+    #[test]
+    fn stuck_rolls() {
+        let dice = Box::new(ScriptedDice::new(vec![1, 2, 3, 4]).unwrap());
+        let mut engine = LudoEngine::new(vec![Rang::Red, Rang::Blue], Rules::preset("mensch").unwrap(), dice).unwrap();
+
+        for _ in 0..3 {
+            assert_eq!(engine.current_player(), Rang::Red);
+            engine.roll().unwrap();
+            engine.pass().unwrap();
+            engine.end_turn().unwrap();
+        }
+        assert_eq!(engine.current_player(), Rang::Blue);
+    }
+
+    // Each dice moves a goti of its own, & doubles roll again
+    #[test]
+    fn split_dice_and_doubles() {
+        let mut engine = parcheesi_at("R:L,L,3,10/B:L,L,L,L R 0/0", vec![2, 4, 1, 2, 3, 3]);
+
+        engine.roll().unwrap();
+        engine.choose(2, &Move::Advance { from: Position::Track(3), to: Position::Track(5) }).unwrap();
+        assert_eq!(engine.get_left(), [4]);
+        engine.choose(4, &Move::Advance { from: Position::Track(10), to: Position::Track(14) }).unwrap();
+        assert_eq!(engine.get_positions(Rang::Red), [Position::Track(5), Position::Track(14)]);
+        engine.end_turn().unwrap();
+        assert_eq!(engine.current_player(), Rang::Blue);
+
+        // Blue has nothing out, Red rolls doubles next
+        engine.roll().unwrap();
+        engine.pass().unwrap();
+        engine.end_turn().unwrap();
+
+        engine.roll().unwrap();
+        engine.choose(3, &Move::Advance { from: Position::Track(5), to: Position::Track(8) }).unwrap();
+        engine.choose(3, &Move::Advance { from: Position::Track(8), to: Position::Track(11) }).unwrap();
+        engine.end_turn().unwrap();
+        assert_eq!(engine.current_player(), Rang::Red);
+    }
+}
@@ -1,23 +1,187 @@
 mod player;
+mod record;
+mod replay;
+mod save;
+mod simulate;
 
+use std::collections::BTreeMap as Map;
 use std::io::{stdin, stdout, Write};
+use std::path::Path;
 
+use crate::bot::{new_bot, Strategy, BOT_KINDS, DEFAULT_BOT};
 use crate::display::Display;
-use crate::engine::MoveResult;
-use crate::engine::{dice::roll, LudoEngine, Rang};
+use crate::engine::{
+    dice::{Dice, SeededDice},
+    EngineState, LudoCellType, LudoEngine, Move, Position, Rang, Rules, TurnState,
+};
 
 use crossterm::style::Color;
 use player::Player;
+use record::{GameRecord, MoveRecord, DEFAULT_RECORD_FILE};
+use save::{SavedGame, DEFAULT_SAVE_FILE, SAVE_VERSION};
+
+pub use record::Choice;
+pub use replay::Replay;
+pub use simulate::Simulation;
+
+fn choice_of(mv: &Move) -> Choice {
+    match mv.from() {
+        Position::Locked => Choice::Unlock,
+        from => Choice::Move(from),
+    }
+}
+
+// Plays of the current turn as (value, choice), in the same order as `LudoEngine::get_plays()`
+pub(crate) fn get_plays(engine: &LudoEngine) -> Vec<(u8, Choice)> {
+    engine
+        .get_plays()
+        .iter()
+        .map(|(value, mv)| (*value, choice_of(mv)))
+        .collect()
+}
+
+// The move of the current turn `choice` stands for with `value`, None if it isn't one (or is `Choice::Pass`)
+pub(crate) fn find_move(engine: &LudoEngine, value: u8, choice: &Choice) -> Option<Move> {
+    engine
+        .get_plays()
+        .into_iter()
+        .find(|(v, mv)| *v == value && choice_of(mv) == *choice)
+        .map(|(_, mv)| mv)
+}
+
+// Plays `choice` in the current turn and returns its record, nothing is printed
+// `choice` must be one of `get_plays()`, or `Choice::Pass` if there are none
+// Note: `dice` of the record is left empty, it is written with the first move of a turn only
+pub(crate) fn play_choice(engine: &mut LudoEngine, value: u8, choice: &Choice) -> MoveRecord {
+    let mut record = MoveRecord {
+        colour: engine.get_current_colour(),
+        dice: Vec::new(),
+        roll: value,
+        choice: choice.clone(),
+        result: None,
+        captured: Vec::new(),
+    };
+
+    if *choice == Choice::Pass {
+        engine
+            .pass()
+            .expect("Passed with a move possible... this is a bug, please report at https://github.com/ludo-game-self.engine/issues");
+        return record;
+    }
+
+    let mv = find_move(engine, value, choice)
+        .expect("Chosen option is not a legal move... this is a bug, please report at https://github.com/ludo-game-self.engine/issues");
+    let result = engine
+        .choose(value, &mv)
+        .expect("Could not play a legal move... this is a bug, please report at https://github.com/ludo-game-self.engine/issues");
+
+    if let Move::Capture { victims, .. } = mv {
+        record.captured = victims;
+    }
+    record.result = Some(result);
+
+    record
+}
+
+// Name of a player is "bot <kind> [settings]" (or just "bot") for a computer player
+fn parse_bot_kind(name: &str) -> Option<String> {
+    let mut words = name.split_whitespace();
+
+    if words.next() != Some("bot") {
+        return None;
+    }
+
+    let spec = words.collect::<Vec<_>>().join(" ");
+    Some(if spec.is_empty() { DEFAULT_BOT.to_string() } else { spec })
+}
+
+// Eg. "6", or "5, 4" for two dice
+fn dice_text(dice: &[u8]) -> String {
+    dice.iter().map(|d| d.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+fn colour_icon(colour: Rang) -> char {
+    match colour {
+        Rang::Red => '🔴',
+        Rang::Green => '🟢',
+        Rang::Yellow => '🟡',
+        Rang::Blue => '🔵',
+    }
+}
+
+// (coord, content, whether the square is safe under the rules played with)
+fn get_display_content(engine: &LudoEngine) -> Vec<((u8, u8), String, bool)> {
+    // `display` component requires this
+    let mut display_content = Vec::new();
+    let board = engine.get_board();
+
+    for (i, row) in board.iter().enumerate() {
+        for (j, cell) in row.iter().enumerate() {
+            if !cell.gotis.is_empty() {
+                // Invariant: Assuming all gotis in one cell, even if multiple, are of same color
+                let goti = cell.gotis[0];
+                let mut content = colour_icon(goti.colour).to_string();
+
+                // '#' in place of count marks a blockade
+                if engine.get_blockade(goti.colour, goti.position).is_some() {
+                    content.push('#');
+                } else if cell.gotis.len() > 1 {
+                    content.push_str(&cell.gotis.len().to_string())
+                }
+
+                display_content.push(((i as u8, j as u8), content, cell.cell_type == LudoCellType::SafeSpot));
+
+                // Note: Not handling case of multiple gotis of different colors, in same cell, eg. "RG", "RGRB" which should be shown as "R2GB"
+            }
+        }
+    }
+
+    for colour in engine.get_active_colours() {
+        if engine.is_finished(*colour) {
+            display_content.push((Rang::GetEndCoord(*colour), "👑".to_string(), false));
+        }
+    }
+
+    display_content
+}
+
+// How to start the game
+pub enum StartFrom<'a> {
+    NewGame,
+    SavedGame(&'a Path),
+    Position(&'a str), // see engine/notation.rs for the format
+}
 
 pub struct LudoGame {
     engine: LudoEngine, // actual logic
     display: Display,
     active_players: Vec<Player>, // order matters !
+    record: GameRecord,          // every roll & move till now
+    bots: Map<Rang, Box<dyn Strategy>>, // seats played by computer
+
+    allow_undo: bool,                        // house rule, players can take back their moves
+    undo_stack: Vec<(Snapshot, Vec<u8>)>,    // start of each turn, and roll in that turn
+    redo_stack: Vec<Snapshot>,               // end of each undone turn
+    turn_start: Option<(Snapshot, Vec<u8>)>, // start of current turn, pushed to `undo_stack` once it's over
+    pending_roll: Option<Vec<u8>>,           // roll to use instead of rolling dice, after an undo
+}
+
+// Everything needed to go back to some turn
+struct Snapshot {
+    engine: EngineState,
+    record: GameRecord,
+    pending_roll: Option<Vec<u8>>,
 }
 
 impl LudoGame {
-    pub fn new() -> Self {
+    // `rules` are not used for a saved game, it continues with the rules it was started with
+    pub fn new(dice: Box<dyn Dice>, rules: Rules, start: StartFrom) -> Self {
         let display = Display::new();
+
+        if let StartFrom::SavedGame(path) = start {
+            return LudoGame::resume(display, dice, path);
+        }
+
         let mut active_players = Vec::new();
         let mut active_colours = Vec::new();
 
@@ -30,13 +194,29 @@ impl LudoGame {
             }
         }
 
-        let engine = LudoEngine::new(active_colours);
+        let engine = match start {
+            StartFrom::Position(notation) => {
+                let engine = LudoEngine::from_notation(notation, rules, dice)
+                    .unwrap_or_else(|e| LudoGame::fail(e.to_string()));
+
+                if engine.get_state().active_colours != active_colours {
+                    LudoGame::fail(format!(
+                        "Position is for {:?}, but players entered for {:?}",
+                        engine.get_state().active_colours,
+                        active_colours
+                    ));
+                }
+                engine
+            }
+            _ => LudoEngine::new(active_colours, rules, dice).unwrap_or_else(|e| LudoGame::fail(e.to_string())),
+        };
 
         for (i, name) in player_names.iter().enumerate() {
             if !name.is_empty() {
                 active_players.push(Player {
                     name: name.clone(),
                     colour: colors[i],
+                    bot: parse_bot_kind(name),
                 })
             }
         }
@@ -47,171 +227,508 @@ impl LudoGame {
             panic!("No players entered");
         }
 
+        let mut record = GameRecord::default();
+        for player in active_players.iter() {
+            record.set_tag(&format!("{:?}", player.colour), &player.name);
+        }
+        if let Some(seed) = engine.get_seed() {
+            record.set_tag("Seed", &seed.to_string());
+        }
+        if let StartFrom::Position(notation) = start {
+            record.set_tag("Position", notation);
+        }
+        record.set_tag("Rules", &engine.get_rules().to_tag());
+        record.set_tag("Result", "*");
+
+        let bots = LudoGame::create_bots(&active_players, engine.get_seed());
+
         LudoGame {
             active_players,
             display,
             engine,
+            record,
+            bots,
+            allow_undo: true,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            turn_start: None,
+            pending_roll: None,
         }
     }
 
-    fn update_display(&self) {
-        // `display` component requires this
-        let mut display_content = Vec::new();
-        let board = &self.engine.get_board();
-
-        for (i, row) in board.iter().enumerate() {
-            for (j, cell) in row.iter().enumerate() {
-                if !cell.gotis.is_empty() {
-                    // Invariant: Assuming all gotis in one cell, even if multiple, are of same color
-                    let mut content = match cell.gotis[0].borrow().colour {
-                        Rang::Red => '🔴',
-                        Rang::Green => '🟢',
-                        Rang::Yellow => '🟡',
-                        Rang::Blue => '🔵',
-                    }
-                    .to_string();
+    fn create_bots(players: &[Player], seed: Option<u64>) -> Map<Rang, Box<dyn Strategy>> {
+        let seed = seed.unwrap_or_else(rand::random);
+        let mut bots = Map::new();
 
-                    if cell.gotis.len() > 1 {
-                        content.push_str(&cell.gotis.len().to_string())
-                    }
+        for (i, player) in players.iter().enumerate() {
+            if let Some(kind) = &player.bot {
+                let bot = new_bot(kind, seed.wrapping_add(i as u64)).unwrap_or_else(|| {
+                    LudoGame::fail(format!("Unknown bot: {:?}, choose from {:?}", kind, BOT_KINDS))
+                });
+                bots.insert(player.colour, bot);
+            }
+        }
 
-                    display_content.push(((i as u8, j as u8), content));
+        bots
+    }
 
-                    // Note: Not handling case of multiple gotis of different colors, in same cell, eg. "RG", "RGRB" which should be shown as "R2GB"
-                }
+    fn fail(message: String) -> ! {
+        Display::splash_screen(&message, Some(Color::Red));
+        std::thread::sleep(std::time::Duration::from_secs(10));
+        panic!("{}", message);
+    }
+
+    fn resume(display: Display, dice: Box<dyn Dice>, path: &Path) -> Self {
+        let fail = LudoGame::fail;
+
+        let saved = SavedGame::load(path).unwrap_or_else(|e| fail(e));
+
+        let player_colours: Vec<Rang> = saved.players.iter().map(|p| p.colour).collect();
+        if player_colours != saved.engine.active_colours {
+            fail(format!(
+                "Players {:?} don't match the colours in game {:?}",
+                player_colours, saved.engine.active_colours
+            ));
+        }
+
+        // Continue with the same rolls as the saved game would have got, unless a dice with that same seed was given
+        let dice = match saved.engine.seed {
+            Some(seed) if dice.seed() != Some(seed) => Box::new(SeededDice::new(seed)),
+            _ => dice,
+        };
+
+        let engine = LudoEngine::from_state(&saved.engine, dice).unwrap_or_else(|e| fail(e.to_string()));
+        let record = GameRecord::from_text(&saved.record).unwrap_or_else(|e| fail(e));
+        let bots = LudoGame::create_bots(&saved.players, engine.get_seed());
+
+        LudoGame {
+            active_players: saved.players,
+            display,
+            engine,
+            record,
+            bots,
+            allow_undo: true,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            turn_start: None,
+            pending_roll: saved.pending_roll,
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        SavedGame {
+            version: SAVE_VERSION,
+            players: self
+                .active_players
+                .iter()
+                .map(|p| Player {
+                    name: p.name.clone(),
+                    colour: p.colour,
+                    bot: p.bot.clone(),
+                })
+                .collect(),
+            engine: self.engine.get_state(),
+            record: self.record.to_text(),
+            pending_roll: self.pending_roll.clone(),
+        }
+        .save(path)
+    }
+
+    pub fn set_allow_undo(&mut self, allow_undo: bool) {
+        self.allow_undo = allow_undo;
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            engine: self.engine.get_state(),
+            record: self.record.clone(),
+            pending_roll: self.pending_roll.clone(),
+        }
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.engine
+            .restore_state(&snapshot.engine)
+            .expect("Snapshot of engine couldn't be restored... this is a bug, please report at https://github.com/ludo-game-self.engine/issues");
+        self.record = snapshot.record;
+        self.pending_roll = snapshot.pending_roll;
+    }
+
+    // Goes back to start of the last turn, that turn will get the same roll again
+    fn undo(&mut self) -> Result<(), String> {
+        if !self.allow_undo {
+            return Err("Undo is not allowed in this house".to_string());
+        }
+
+        let (snapshot, roll) = self.undo_stack.pop().ok_or("Nothing to undo")?;
+
+        self.redo_stack.push(self.snapshot());
+        self.restore(snapshot);
+        self.pending_roll = Some(roll);
+
+        Ok(())
+    }
+
+    fn redo(&mut self) -> Result<(), String> {
+        if !self.allow_undo {
+            return Err("Undo is not allowed in this house".to_string());
+        }
+
+        let snapshot = self.redo_stack.pop().ok_or("Nothing to redo")?;
+
+        // SAFETY: Only an undo puts something in `redo_stack`, and that always sets `pending_roll`
+        let roll = self.pending_roll.take().unwrap();
+        self.undo_stack.push((self.snapshot(), roll));
+        self.restore(snapshot);
+
+        Ok(())
+    }
+
+    // Starts a turn with the roll after an undo if there is one, else rolls the dice
+    fn roll_dice(&mut self) -> Vec<u8> {
+        let turn_start = self.snapshot();
+        let dice = match self.pending_roll.take() {
+            Some(dice) => {
+                self.engine
+                    .play_roll(&dice)
+                    .expect("Roll after undo couldn't be played... this is a bug, please report at https://github.com/ludo-game-self.engine/issues");
+                dice
+            }
+            None => self
+                .engine
+                .roll()
+                .expect("Dice couldn't be rolled... this is a bug, please report at https://github.com/ludo-game-self.engine/issues"),
+        };
+        self.turn_start = Some((turn_start, dice.clone()));
+
+        dice
+    }
+
+    // Adds the move to the record, and the colour to `Result` tag if it just finished
+    fn record_move(&mut self, mut record: MoveRecord) {
+        // A new turn is played, so the undone ones can't be redone anymore
+        // Its first move is the one written with the dice
+        if let Some((turn_start, dice)) = self.turn_start.take() {
+            record.dice = dice.clone();
+            self.undo_stack.push((turn_start, dice));
+            self.redo_stack.clear();
+        }
+
+        let colour = record.colour;
+        self.record.moves.push(record);
+
+        if self.engine.is_finished(colour) {
+            let mut result = self.record.get_tag("Result").unwrap_or("*").replace('*', "");
+            if !result.contains(Rang::GetLetter(colour)) {
+                result.push(Rang::GetLetter(colour));
+            }
+            if !self.engine.is_game_finished() {
+                result.push('*');
             }
+
+            self.record.set_tag("Result", &result);
+        }
+    }
+
+    // Colours in the order they finished, then the ones left by their score
+    // With `Rules::teams`, partners are placed together and the winning team comes first
+    fn get_standings(&self) -> Vec<Rang> {
+        let mut standings = self.engine.get_finish_order().to_vec();
+
+        let mut rest: Vec<Rang> = self
+            .engine
+            .get_active_colours()
+            .iter()
+            .filter(|c| !standings.contains(c))
+            .copied()
+            .collect();
+        // Stable, so a tie keeps order of play
+        rest.sort_by_key(|c| std::cmp::Reverse(self.engine.get_score(*c)));
+
+        standings.extend(rest);
+        if !self.engine.get_rules().teams {
+            return standings;
         }
 
-        for player in self.active_players.iter() {
-            if self.engine.is_finished(player.colour) {
-                display_content.push((Rang::GetEndCoord(player.colour), "👑".to_string()));
+        let winners = self.engine.get_winners();
+        let (mut grouped, rest): (Vec<Rang>, Vec<Rang>) = standings.iter().partition(|c| winners.contains(c));
+        for colour in rest.iter() {
+            if grouped.contains(colour) {
+                continue;
+            }
+
+            grouped.push(*colour);
+            let partner = Rang::GetPartner(*colour);
+            if rest.contains(&partner) {
+                grouped.push(partner);
             }
         }
+        grouped
+    }
+
+    fn get_name(&self, colour: Rang) -> String {
+        self.active_players
+            .iter()
+            .find(|p| p.colour == colour)
+            .map_or(format!("{:?}", colour), |p| p.name.clone())
+    }
 
-        self.display.update_display(display_content.clone());
+    // Final standings screen, these also go in the `Result` tag
+    fn show_standings(&mut self) {
+        let standings = self.get_standings();
+        let rules = self.engine.get_rules();
+
+        // Game is over here, so there is a winner
+        let winners: Vec<String> = self.engine.get_winners().iter().map(|c| self.get_name(*c)).collect();
+        let winner = winners.first().cloned().unwrap_or_default();
+        let title = if rules.teams {
+            format!("Team {} wins!", winners.join(" & "))
+        } else if rules.rounds > 0 && self.engine.get_num_rounds() >= rules.rounds {
+            format!("{} wins on points after {} rounds!", winner, rules.rounds)
+        } else {
+            format!("{} wins!", winner)
+        };
+
+        let places = ["1st", "2nd", "3rd", "4th"];
+        let lines: Vec<String> = standings
+            .iter()
+            .enumerate()
+            .map(|(i, colour)| {
+                format!(
+                    "{}  {} {:<20} finished {}/{}, captured {}, score {}",
+                    places[i],
+                    colour_icon(*colour),
+                    self.get_name(*colour),
+                    self.engine.get_num_finished(*colour),
+                    rules.gotis_per_colour,
+                    self.engine.get_num_captures(*colour),
+                    self.engine.get_score(*colour)
+                )
+            })
+            .collect();
+
+        let result: String = standings.iter().map(|c| Rang::GetLetter(*c)).collect();
+        self.record.set_tag("Result", &result);
+
+        self.display.standings_screen(&title, &lines);
+    }
+
+    fn update_display(&self) {
+        self.display.update_display(get_display_content(&self.engine));
     }
 
     pub fn play(&mut self) {
         self.update_display();
 
-        // Will be updated at end of each iteration
-        let mut player_index = 0;
-        let mut same_player_next_chance;
         loop {
-            // for player in self.active_players.iter() {
-            same_player_next_chance = false; // may later be modified, if for eg. '6', or finishes etc.
+            // Engine keeps the turn, a bonus turn or skipping the finished ones is all decided there
+            match self.engine.get_turn_state() {
+                TurnState::GameOver => break,
+                TurnState::TurnOver => {
+                    self.engine
+                        .end_turn()
+                        .expect("Turn couldn't be ended... this is a bug, please report at https://github.com/ludo-game-self.engine/issues");
+                    continue;
+                }
+                TurnState::AwaitingRoll | TurnState::AwaitingMove => {}
+            }
 
-            let player = &self.active_players[player_index];
+            let player = self.engine.current_player();
+            let name = self.get_name(player);
+            // Same as `player`, unless it has finished & plays for its partner
+            let colour = self.engine.get_current_colour();
 
-            if self.engine.is_game_finished() {
-                break;
-            } else if self.engine.is_finished(player.colour) {
-                continue;
+            let mut header = name.clone();
+            if colour != player {
+                header.push_str(&format!(" (moving {:?} for the partner)", colour));
             }
-
-            self.engine.set_current_colour(player.colour);
-            self.display.set_player(&player.name);
+            if !self.engine.can_enter_home(colour) {
+                header.push_str(" (capture a goti to enter home)");
+            }
+            let rounds = self.engine.get_rules().rounds;
+            if rounds > 0 {
+                header.push_str(&format!(" - round {}/{}", self.engine.get_num_rounds() + 1, rounds));
+            }
+            self.display.set_player(&header);
             self.update_display();
 
-            print!("Press Enter to Roll: ");
-            stdout().flush().unwrap();
-            // ignore input till Enter
-            let mut ignore_buf = String::new();
-            stdin().read_line(&mut ignore_buf).unwrap();
-            ignore_buf.clear();
-
-            let roll = roll();
-
-            println!("Roll Output - {:?}", roll);
+            if let Some(roll) = &self.pending_roll {
+                println!("Roll after undo - {}", dice_text(roll));
+            }
 
-            if roll == 6 {
-                same_player_next_chance = true;
+            // ignore input till Enter, unless a command
+            let mut command = String::new();
+            if self.engine.get_turn_state() == TurnState::AwaitingMove {
+                // Rest of the roll is still to be played
+            } else if self.bots.contains_key(&player) {
+                println!("{} is rolling...", name);
+            } else {
+                print!("Press Enter to Roll (or type 'save [file]' to save & quit, 'record [file]' to write game record, 'pos' to print position, 'undo', 'redo'): ");
+                stdout().flush().unwrap();
+                stdin().read_line(&mut command).unwrap();
             }
 
-            let movable_gotis = self.engine.get_movable_gotis(player.colour, roll);
+            if command.trim() == "pos" {
+                // For bug reports etc., can be given back with `--position`
+                println!("{}", self.engine.to_notation());
+                print!("Press Enter to continue");
+                stdout().flush().unwrap();
+                stdin().read_line(&mut command).unwrap();
+                continue;
+            }
 
-            if (roll == 6 && self.engine.get_num_locked(player.colour).unwrap() > 0)
-                || !movable_gotis.is_empty()
-            {
-                println!("Chose from these options: ");
+            if command.trim() == "undo" || command.trim() == "redo" {
+                let mut result = if command.trim() == "undo" { self.undo() } else { self.redo() };
 
-                let mut i = 0;
-                if roll == 6 && self.engine.get_num_locked(player.colour).unwrap() > 0 {
-                    println!("0. Unlock New Goti (just type 0)");
-                    i += 1;
+                // Bots would just play again, so go on till a human's turn
+                while result.is_ok() && self.bots.contains_key(&self.engine.current_player()) {
+                    let again = if command.trim() == "undo" { self.undo() } else { self.redo() };
+                    if again.is_err() {
+                        break;
+                    }
+                    result = again;
                 }
 
-                for c in movable_gotis.iter() {
-                    println!("{}. [{}][{}]", i, c.0, c.1);
-                    i += 1;
+                if let Err(e) = result {
+                    println!("{}", e);
+                    std::thread::sleep(std::time::Duration::from_secs(1));
                 }
+                continue;
+            }
 
-                let mut input = String::new();
-                stdin().read_line(&mut input).expect("Failed to read input");
+            if let Some(path) = command.trim().strip_prefix("record") {
+                let path = if path.trim().is_empty() { DEFAULT_RECORD_FILE } else { path.trim() };
 
-                let trimmed = input.trim();
-                let mut chosen_option = match trimmed.parse::<u8>() {
-                    Ok(i) => i,
-                    Err(_) => {
-                        println!("Not a option: {:?}", trimmed);
-                        println!("Repeating...");
-                        // Probable bug: Dice output wont be same next time
+                match self.record.save(Path::new(path)) {
+                    Ok(()) => println!("Game record written to {:?}", path),
+                    Err(e) => println!("Couldn't write game record: {}", e),
+                }
+                std::thread::sleep(std::time::Duration::from_secs(1));
+                continue;
+            }
+
+            if let Some(path) = command.trim().strip_prefix("save") {
+                let path = if path.trim().is_empty() { DEFAULT_SAVE_FILE } else { path.trim() };
 
+                match self.save(Path::new(path)) {
+                    Ok(()) => {
+                        println!("Game saved to {:?}, continue with `--resume {}`", path, path);
+                        return;
+                    }
+                    Err(e) => {
+                        println!("Couldn't save: {}", e);
                         std::thread::sleep(std::time::Duration::from_secs(1));
                         continue;
                     }
-                };
+                }
+            }
 
-                let mut skip_rest = true;
+            if self.engine.get_turn_state() == TurnState::AwaitingRoll {
+                let dice = self.roll_dice();
+                println!("Roll Output - {}", dice_text(&dice));
+
+                if self.engine.get_turn_state() == TurnState::TurnOver {
+                    println!(
+                        "Third 6 in a row, turn is over ({:?} rule)",
+                        self.engine.get_rules().three_sixes
+                    );
+                    self.record_move(MoveRecord {
+                        colour,
+                        roll: dice[0],
+                        dice: Vec::new(),
+                        choice: Choice::Pass,
+                        result: None,
+                        captured: Vec::new(),
+                    });
+
+                    std::thread::sleep(std::time::Duration::from_secs(1));
+                    continue;
+                }
+            } else {
+                println!("Left to play - {}", dice_text(self.engine.get_left()));
+            }
+
+            let options = get_plays(&self.engine);
 
-                if roll == 6 && self.engine.get_num_locked(player.colour).unwrap() > 0 {
-                    same_player_next_chance = true;
+            let (value, choice) = if options.is_empty() {
+                println!("No possible moves...");
+                (self.engine.get_left()[0], Choice::Pass)
+            } else {
+                println!("Chose from these options: ");
 
-                    if chosen_option == 0 {
-                        self.engine.unlock_goti(player.colour)
-                            .expect("No Goti to unlock... this is a bug, please report at https://github.com/ludo-game-self.engine/issues");
-                    } else {
-                        chosen_option -= 1;
-                        skip_rest = false;
+                // Which value moves it only matters if there are more than one
+                let one_value = options.iter().all(|(value, _)| *value == options[0].0);
+                for (i, (value, choice)) in options.iter().enumerate() {
+                    let with = if one_value { String::new() } else { format!(" with {}", value) };
+
+                    match choice {
+                        Choice::Unlock => println!("{}. Unlock New Goti{} (just type {})", i, with, i),
+                        Choice::Move(from) => {
+                            // SAFETY: A goti that moves is on the track or in the home lane, both are on the board
+                            let (r, c) = from.to_coord(colour).unwrap();
+                            println!("{}. [{}][{}]{}", i, r, c, with)
+                        }
+                        Choice::Pass => {}
                     }
-                } else {
-                    skip_rest = false;
                 }
 
-                if !skip_rest {
-                    // Choice is one of `movable_gotis`
-                    match movable_gotis.get(chosen_option as usize) {
-                        Some(start_coord) => {
-                            let result = self.engine.move_goti(player.colour, *start_coord, roll)
-                                        .expect("Could not move, although .get_movable_gotis() said i can :(...  this is a bug, please report at https://github.com/ludo-game-self.engine/issues");
-
-                            match result {
-                                MoveResult::Attacked(_)
-                                | MoveResult::Finished
-                                | MoveResult::Unlocked => {
-                                    same_player_next_chance = true;
-                                }
-                                MoveResult::NormalMove(_) => {}
-                            };
-                        }
-                        None => {
-                            println!("Invalid choice: {:?}", chosen_option);
+                let chosen_option = match self.bots.get_mut(&player) {
+                    Some(bot) => {
+                        let chosen = bot.choose_play(&self.engine, colour, &self.engine.get_plays());
+                        println!("{} ({} bot) chose: {}", name, bot.name(), chosen);
+                        std::thread::sleep(std::time::Duration::from_secs(1));
+
+                        chosen
+                    }
+                    None => {
+                        let mut input = String::new();
+                        stdin().read_line(&mut input).expect("Failed to read input");
+
+                        let trimmed = input.trim();
+                        match trimmed.parse::<usize>() {
+                            Ok(i) => i,
+                            Err(_) => {
+                                println!("Not a option: {:?}", trimmed);
+                                println!("Repeating...");
+                                std::thread::sleep(std::time::Duration::from_secs(1));
+                                continue;
+                            }
                         }
                     }
+                };
+
+                match options.get(chosen_option) {
+                    Some(option) => option.clone(),
+                    None => {
+                        println!("Invalid choice: {:?}", chosen_option);
+                        println!("Repeating...");
+                        std::thread::sleep(std::time::Duration::from_secs(1));
+                        continue;
+                    }
                 }
-            } else {
-                println!("No possible moves...");
-            }
+            };
 
-            if same_player_next_chance == false {
-                // Next player
-                player_index = (player_index + 1) % self.active_players.len();
-            }
+            let move_record = play_choice(&mut self.engine, value, &choice);
+            self.record_move(move_record);
 
             std::thread::sleep(std::time::Duration::from_secs(1));
         }
 
-        // !TODO
+        self.show_standings();
+
+        match self.record.save(Path::new(DEFAULT_RECORD_FILE)) {
+            Ok(()) => println!("Game record written to {:?}", DEFAULT_RECORD_FILE),
+            Err(e) => println!("Couldn't write game record: {}", e),
+        }
+
+        if let Some(seed) = self.engine.get_seed() {
+            println!("Game seed: {} (replay same rolls with `--seed {}`)", seed, seed);
+        }
+
+        print!("Press Enter to exit");
+        stdout().flush().unwrap();
+        let mut input = String::new();
+        stdin().read_line(&mut input).unwrap();
     }
 }
 
@@ -220,3 +737,103 @@ impl Drop for LudoGame {
         self.display.end_display();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{dice::ScriptedDice, MoveResult};
+
+    fn game_at(notation: &str, rolls: Vec<u8>) -> LudoGame {
+        let engine =
+            LudoEngine::from_notation(notation, Rules::default(), Box::new(ScriptedDice::new(rolls).unwrap())).unwrap();
+        let active_players = engine
+            .get_active_colours()
+            .iter()
+            .map(|c| Player { name: format!("{:?}", c), colour: *c, bot: None })
+            .collect();
+
+        LudoGame {
+            engine,
+            display: Display::new(),
+            active_players,
+            record: GameRecord::default(),
+            bots: Map::new(),
+            allow_undo: true,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            turn_start: None,
+            pending_roll: None,
+        }
+    }
+
+    // Rolls & plays the first option with `choice`, like `play()` would
+    fn play_turn(game: &mut LudoGame, choice: &Choice) {
+        game.roll_dice();
+        let (value, _) = get_plays(&game.engine).into_iter().find(|(_, c)| c == choice).unwrap();
+        let record = play_choice(&mut game.engine, value, choice);
+        game.record_move(record);
+    }
+
+    // Red goti on the star square, 8 steps from its start
+    #[test]
+    fn safe_squares_follow_the_rules() {
+        let coord = Position::Track(8).to_coord(Rang::Red).unwrap();
+        let is_safe = |rules: Rules| {
+            let engine = LudoEngine::from_notation("R:L,L,L,8/B:L,L,L,L R 0/0", rules, Box::new(ScriptedDice::new(vec![1]).unwrap())).unwrap();
+            get_display_content(&engine).iter().find(|(c, _, _)| *c == coord).unwrap().2
+        };
+
+        assert!(is_safe(Rules::default()));
+        assert!(!is_safe(Rules::preset("mensch").unwrap()));
+    }
+
+    #[test]
+    fn undo_and_redo_a_capture() {
+        // Red 3 + 2 lands on Blue
+        let mut game = game_at("R:L,L,L,3/B:L,L,L,18 R 0/0", vec![2]);
+        let before = game.engine.get_state();
+
+        play_turn(&mut game, &Choice::Move(Position::Track(3)));
+        let after = game.engine.get_state();
+        assert_eq!(game.engine.get_num_locked(Rang::Blue), Some(4));
+        assert_eq!(game.engine.get_num_captures(Rang::Red), 1);
+
+        game.undo().unwrap();
+        assert_eq!(game.engine.get_state().moving_gotis, before.moving_gotis);
+        assert_eq!(game.engine.get_num_locked(Rang::Blue), Some(3));
+        assert_eq!(game.engine.get_num_captures(Rang::Red), 0);
+        assert!(game.record.moves.is_empty());
+        assert_eq!(game.pending_roll, Some(vec![2]));
+
+        // The undone roll survives a save
+        let path = std::env::temp_dir().join("ludo-undo-test-save.json");
+        game.save(&path).unwrap();
+        let resumed = LudoGame::resume(Display::new(), Box::new(ScriptedDice::new(vec![5]).unwrap()), &path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(resumed.pending_roll, Some(vec![2]));
+        assert_eq!(resumed.engine.get_state(), game.engine.get_state());
+
+        game.redo().unwrap();
+        assert_eq!(game.engine.get_state(), after);
+        assert_eq!(game.record.moves[0].captured, [Rang::Blue]);
+        assert_eq!(game.pending_roll, None);
+        assert!(game.redo().is_err());
+    }
+
+    #[test]
+    fn undo_and_redo_a_finish() {
+        let mut game = game_at("R:L,L,L,55/B:L,L,L,18 R 0/0", vec![1]);
+
+        play_turn(&mut game, &Choice::Move(Position::HomeLane(4)));
+        assert_eq!(game.engine.get_num_finished(Rang::Red), 1);
+
+        game.undo().unwrap();
+        assert_eq!(game.engine.get_num_finished(Rang::Red), 0);
+        assert_eq!(game.engine.get_positions(Rang::Red), [Position::HomeLane(4)]);
+
+        game.redo().unwrap();
+        assert_eq!(game.engine.get_num_finished(Rang::Red), 1);
+        assert!(game.engine.get_positions(Rang::Red).is_empty());
+        assert_eq!(game.record.moves[0].result, Some(MoveResult::Finished));
+    }
+}
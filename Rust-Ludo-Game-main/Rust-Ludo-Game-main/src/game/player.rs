@@ -1,6 +1,10 @@
+use serde::{Deserialize, Serialize};
+
 use crate::engine::Rang;
 
+#[derive(Serialize, Deserialize)]
 pub struct Player {
     pub name: String,
     pub colour: Rang,
+    pub bot: Option<String>, // kind of bot, if a computer plays this seat, see `bot::BOT_KINDS`
 }
@@ -0,0 +1,310 @@
+// Game record, similar to PGN in chess. Eg.
+//
+//      [Red "Tarik"]
+//      [Blue "Ahmed"]
+//      [Seed "1234"]
+//      [Rules "Standard"]
+//      [Result "*"]
+//
+//      1. R6:U 2. R4:0-4 3. B3:- 4. R2:4-6xB 5. B6:U 6. B5:48-53 7. B2:53-F
+//
+// Tags: `[Name "Value"]`, one per line, with `\"` and `\\` for quote and backslash in value. Names of players are under their colour
+//      Seed     - seed of the dice, if it was reproducible
+//      Position - starting position if not the usual one (see engine/notation.rs)
+//      Rules    - rule set played with, "Standard" or the house rules in JSON (see engine/rules.rs)
+//      Result   - colours in order of finishing, eg. "RB", followed by "*" if game not over yet
+//                 once over, all colours in order of the final standings (the ones left unfinished by score), with
+//                 `Rules::teams` partners are together and the winning team is first
+//
+// Moves: `<number>. <colour><roll>:<choice>`, one for every roll of the dice
+//      With more to play than a single dice in a turn (see `Rules::dice` & `Rules::capture_steps`), the roll is written
+//      as `<dice>/<value>` for the first move of the turn, eg. `R5+4/4:0-4`, and `/<value>` for the rest, eg. `R/5:U`
+//      U           goti unlocked (can capture too, if the start square isn't safe)
+//      -           no move (none possible)
+//      <from>-<to> goti moved, positions as number of steps from the colour's start square
+//                  `to` is `F` if the goti finished
+//      x<colours>  suffix, if gotis of these colours were captured
+// Anything after ';' till end of line is a comment (except in tag lines)
+
+use std::{fs, path::Path};
+
+use crate::engine::{LudoEngine, MoveResult, Position, Rang};
+
+pub const DEFAULT_RECORD_FILE: &str = "ludo-game.lgn";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Choice {
+    Unlock,
+    Move(Position), // where the moved goti started from
+    Pass,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MoveRecord {
+    pub colour: Rang,
+    pub dice: Vec<u8>, // dice rolled before this move, empty if it plays what is left from an earlier one
+    pub roll: u8,      // value played: a dice, dice added up, or bonus steps
+    pub choice: Choice,
+    pub result: Option<MoveResult>, // None if `Choice::Pass`
+    pub captured: Vec<Rang>,        // colours of the captured gotis, one entry per goti
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GameRecord {
+    pub tags: Vec<(String, String)>, // order is kept as it is
+    pub moves: Vec<MoveRecord>,
+}
+
+impl MoveRecord {
+    pub fn to_text(&self) -> String {
+        let steps = |position: Position| match LudoEngine::get_steps_to(position) {
+            Some(steps) => steps.to_string(),
+            None => panic!("{:?} goti can not be moving at {:?}", self.colour, position),
+        };
+
+        let choice = match (&self.choice, &self.result) {
+            (Choice::Unlock, _) => "U".to_string(),
+            (Choice::Move(from), Some(MoveResult::Finished)) => format!("{}-F", steps(*from)),
+            (Choice::Move(from), Some(MoveResult::NormalMove(to)))
+            | (Choice::Move(from), Some(MoveResult::Attacked(to))) => {
+                format!("{}-{}", steps(*from), steps(*to))
+            }
+            _ => "-".to_string(),
+        };
+
+        let roll = if self.dice == [self.roll] {
+            self.roll.to_string()
+        } else {
+            let dice: Vec<String> = self.dice.iter().map(|d| d.to_string()).collect();
+            format!("{}/{}", dice.join("+"), self.roll)
+        };
+
+        let mut text = format!("{}{}:{}", Rang::GetLetter(self.colour), roll, choice);
+        if !self.captured.is_empty() {
+            text.push('x');
+            text.extend(self.captured.iter().map(|c| Rang::GetLetter(*c)));
+        }
+
+        text
+    }
+
+    fn from_text(text: &str) -> Result<Self, String> {
+        let invalid = || format!("Invalid move: {:?}", text);
+
+        let (head, choice) = text.split_once(':').ok_or_else(invalid)?;
+        let mut head = head.chars();
+        let colour = Rang::FromLetter(head.next().ok_or_else(invalid)?).map_err(|e| e.to_string())?;
+        let number = |n: &str| n.parse::<u8>().map_err(|_| invalid());
+        let (dice, roll) = match head.as_str().split_once('/') {
+            Some(("", roll)) => (Vec::new(), number(roll)?),
+            Some((dice, roll)) => (dice.split('+').map(number).collect::<Result<Vec<_>, _>>()?, number(roll)?),
+            None => (vec![number(head.as_str())?], number(head.as_str())?),
+        };
+
+        let (choice, captured) = match choice.split_once('x') {
+            Some((choice, captured)) => (
+                choice,
+                captured.chars().map(Rang::FromLetter).collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())?,
+            ),
+            None => (choice, Vec::new()),
+        };
+
+        let position = |steps: &str| {
+            steps
+                .parse::<u8>()
+                .ok()
+                .and_then(LudoEngine::get_position_at_steps)
+                .ok_or_else(invalid)
+        };
+
+        let (choice, result) = match choice {
+            "U" if captured.is_empty() => (Choice::Unlock, Some(MoveResult::Unlocked)),
+            "U" => (Choice::Unlock, Some(MoveResult::Attacked(Position::Track(0)))),
+            "-" => (Choice::Pass, None),
+            _ => {
+                let (from, to) = choice.split_once('-').ok_or_else(invalid)?;
+                let result = if to == "F" {
+                    MoveResult::Finished
+                } else if captured.is_empty() {
+                    MoveResult::NormalMove(position(to)?)
+                } else {
+                    MoveResult::Attacked(position(to)?)
+                };
+
+                (Choice::Move(position(from)?), Some(result))
+            }
+        };
+
+        Ok(MoveRecord {
+            colour,
+            dice,
+            roll,
+            choice,
+            result,
+            captured,
+        })
+    }
+}
+
+impl GameRecord {
+    pub fn get_tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    // Replaces value if tag already present
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(n, _)| n == name) {
+            Some(tag) => tag.1 = value.to_string(),
+            None => self.tags.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+
+        for (name, value) in self.tags.iter() {
+            let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+            text.push_str(&format!("[{} \"{}\"]\n", name, value));
+        }
+        text.push('\n');
+
+        // Keep lines short, like PGN
+        let mut line = String::new();
+        for (i, m) in self.moves.iter().enumerate() {
+            let token = format!("{}. {}", i + 1, m.to_text());
+
+            if !line.is_empty() && line.len() + token.len() + 1 > 80 {
+                text.push_str(&line);
+                text.push('\n');
+                line.clear();
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&token);
+        }
+        if !line.is_empty() {
+            text.push_str(&line);
+            text.push('\n');
+        }
+
+        text
+    }
+
+    pub fn from_text(text: &str) -> Result<Self, String> {
+        let mut record = GameRecord::default();
+
+        for line in text.lines() {
+            let line = line.trim();
+
+            if let Some(tag) = line.strip_prefix('[') {
+                let (name, value) = tag
+                    .strip_suffix(']')
+                    .and_then(|tag| tag.split_once(' '))
+                    .ok_or_else(|| format!("Invalid tag: {:?}", line))?;
+                let value = value
+                    .trim()
+                    .strip_prefix('"')
+                    .and_then(|v| v.strip_suffix('"'))
+                    .ok_or_else(|| format!("Tag value must be in quotes: {:?}", line))?;
+
+                record.set_tag(name, &GameRecord::unescape(value));
+                continue;
+            }
+
+            let line = match line.split_once(';') {
+                Some((line, _comment)) => line,
+                None => line,
+            };
+
+            for token in line.split_whitespace() {
+                // Move numbers are only for humans
+                if token.ends_with('.') && token[..token.len() - 1].parse::<usize>().is_ok() {
+                    continue;
+                }
+
+                record.moves.push(MoveRecord::from_text(token)?);
+            }
+        }
+
+        Ok(record)
+    }
+
+    // Undoes `\"` and `\\` of tag values
+    fn unescape(value: &str) -> String {
+        let mut unescaped = String::new();
+        let mut chars = value.chars();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => unescaped.extend(chars.next()),
+                c => unescaped.push(c),
+            }
+        }
+
+        unescaped
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        fs::write(path, self.to_text()).map_err(|e| format!("Couldn't write {:?}: {}", path, e))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("Couldn't read {:?}: {}", path, e))?;
+
+        GameRecord::from_text(&text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RECORD: &str = r#"[Red "Tarik \"Goti\" \\ Khan"]
+[Blue "Ahmed"]
+[Result "*"]
+
+1. R5+4/4:0-4 2. R/5:U ; the other dice unlocks
+3. B3:- 4. R2:4-6xBG 5. B6:U 6. B2:53-F
+"#;
+
+    #[test]
+    fn read_and_write() {
+        let record = GameRecord::from_text(RECORD).unwrap();
+
+        assert_eq!(record.get_tag("Red"), Some(r#"Tarik "Goti" \ Khan"#));
+        assert_eq!(record.get_tag("Result"), Some("*"));
+        assert_eq!(record.moves.len(), 6);
+
+        let two_dice = &record.moves[0];
+        assert_eq!((two_dice.dice.as_slice(), two_dice.roll), ([5, 4].as_slice(), 4));
+        assert_eq!(two_dice.choice, Choice::Move(Position::Track(0)));
+        assert_eq!(two_dice.result, Some(MoveResult::NormalMove(Position::Track(4))));
+
+        // Rest of the same roll
+        assert!(record.moves[1].dice.is_empty());
+        assert_eq!((record.moves[1].roll, &record.moves[1].choice), (5, &Choice::Unlock));
+
+        assert_eq!((&record.moves[2].choice, &record.moves[2].result), (&Choice::Pass, &None));
+        assert_eq!(record.moves[3].captured, [Rang::Blue, Rang::Green]);
+        assert_eq!(record.moves[3].result, Some(MoveResult::Attacked(Position::Track(6))));
+        assert_eq!(record.moves[5].choice, Choice::Move(Position::HomeLane(2)));
+        assert_eq!(record.moves[5].result, Some(MoveResult::Finished));
+
+        // Comments are not kept, everything else is
+        let text = record.to_text();
+        assert!(text.contains(r#"[Red "Tarik \"Goti\" \\ Khan"]"#));
+        assert!(text.contains("1. R5+4/4:0-4 2. R/5:U 3. B3:- 4. R2:4-6xBG"));
+        assert_eq!(GameRecord::from_text(&text).unwrap(), record);
+    }
+
+    #[test]
+    fn invalid_moves() {
+        for text in ["1. R7x:0-4", "1. Q6:U", "1. R6:0-60", "1. R6:0", "[Red Tarik]"] {
+            assert!(GameRecord::from_text(text).is_err(), "{:?}", text);
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use std::{
+    io::{stdin, stdout, Write},
+    path::Path,
+    time::Duration,
+};
+
+use crossterm::{
+    event::{self, Event},
+    style::Color,
+    terminal,
+};
+
+use super::{
+    find_move, get_display_content,
+    record::{Choice, GameRecord, MoveRecord},
+};
+use crate::display::Display;
+use crate::engine::{dice::ThreadDice, EngineState, LudoEngine, LudoError, Rang, Rules, TurnState};
+
+// Steps through a recorded game, position by position
+pub struct Replay {
+    display: Display,
+    record: GameRecord,
+    positions: Vec<EngineState>, // positions[i] = after first `i` moves
+    error: Option<String>,       // if the record couldn't be replayed till the end
+    current: usize,
+}
+
+impl Replay {
+    pub fn new(path: &Path) -> Self {
+        let display = Display::new();
+
+        let fail = |message: String| -> ! {
+            Display::splash_screen(&message, Some(Color::Red));
+            std::thread::sleep(Duration::from_secs(10));
+            panic!("{}", message);
+        };
+
+        let record = GameRecord::load(path).unwrap_or_else(|e| fail(e));
+        let rules = match record.get_tag("Rules") {
+            Some(tag) => Rules::from_tag(tag).unwrap_or_else(|e| fail(e)),
+            None => Rules::default(),
+        };
+
+        // Dice isn't used, the rolls are in the record
+        let mut engine = match record.get_tag("Position") {
+            Some(notation) => LudoEngine::from_notation(notation, rules, Box::new(ThreadDice))
+                .unwrap_or_else(|e| fail(e.to_string())),
+            None => {
+                let colours: Vec<Rang> = [Rang::Red, Rang::Green, Rang::Yellow, Rang::Blue]
+                    .into_iter()
+                    .filter(|c| record.get_tag(&format!("{:?}", c)).is_some())
+                    .collect();
+
+                if colours.is_empty() {
+                    fail("No players in the game record".to_string());
+                }
+                LudoEngine::new(colours, rules, Box::new(ThreadDice)).unwrap_or_else(|e| fail(e.to_string()))
+            }
+        };
+
+        let mut positions = vec![engine.get_state()];
+        let mut error = None;
+
+        // Stop at the first move the engine disagrees with, the positions till here are still shown
+        for (i, m) in record.moves.iter().enumerate() {
+            if let Err(e) = Replay::play_move(&mut engine, m) {
+                error = Some(format!("Move {}: {}", i + 1, e));
+                break;
+            }
+
+            positions.push(engine.get_state());
+        }
+
+        Replay {
+            display,
+            record,
+            positions,
+            error,
+            current: 0,
+        }
+    }
+
+    // Plays a recorded move through the turn of `engine`, the same way the game played it
+    fn play_move(engine: &mut LudoEngine, m: &MoveRecord) -> Result<(), String> {
+        if engine.get_turn_state() == TurnState::TurnOver {
+            engine.end_turn().map_err(|e| e.to_string())?;
+        }
+
+        // Dice are written only with the first move of a turn
+        match engine.get_turn_state() {
+            TurnState::GameOver => return Err("game is already over".to_string()),
+            TurnState::AwaitingRoll if m.dice.is_empty() => return Err("dice not given for a new turn".to_string()),
+            TurnState::AwaitingMove if !m.dice.is_empty() => {
+                return Err("new roll before the last one was played".to_string())
+            }
+            _ => {}
+        }
+
+        // Record has the colour moved, a finished colour moves its partner's gotis in its own turn
+        if m.colour != engine.get_current_colour() {
+            return Err(LudoError::NotYourTurn(m.colour).to_string());
+        }
+
+        if engine.get_turn_state() == TurnState::AwaitingRoll {
+            engine.play_roll(&m.dice).map_err(|e| e.to_string())?;
+
+            if engine.get_turn_state() == TurnState::TurnOver {
+                return match m.choice {
+                    Choice::Pass => Ok(()),
+                    _ => Err("third 6 in a row can't be played".to_string()),
+                };
+            }
+        }
+
+        let result = match m.choice {
+            Choice::Pass => {
+                engine.pass().map_err(|e| e.to_string())?;
+                None
+            }
+            _ => {
+                let mv = find_move(engine, m.roll, &m.choice)
+                    .ok_or_else(|| format!("{} can't be played with this roll", m.to_text()))?;
+                Some(engine.choose(m.roll, &mv).map_err(|e| e.to_string())?)
+            }
+        };
+
+        if result != m.result {
+            return Err(format!("recorded {:?}, but engine gave {:?}", m.result, result));
+        }
+
+        Ok(())
+    }
+
+    fn show(&mut self) {
+        let last = self.positions.len() - 1;
+        let header = match self.current {
+            0 => format!("Start (0/{})", last),
+            i => format!("Move {}/{}: {}", i, last, self.record.moves[i - 1].to_text()),
+        };
+        self.display.set_player(&header);
+
+        // Positions are already checked while building them, so this won't fail
+        let engine = LudoEngine::from_state(&self.positions[self.current], Box::new(ThreadDice)).unwrap();
+        self.display.update_display(get_display_content(&engine));
+
+        if let Some(error) = &self.error {
+            println!("Replay stops early, {}", error);
+        }
+    }
+
+    // Keeps moving forward every `delay`, till last move or a key is pressed
+    fn autoplay(&mut self, delay: Duration) {
+        while self.current + 1 < self.positions.len() {
+            self.current += 1;
+            self.show();
+            println!("Autoplaying... press any key to stop");
+
+            terminal::enable_raw_mode().unwrap();
+            let key_pressed = event::poll(delay).unwrap_or(false);
+            if key_pressed {
+                // Consume that key
+                while !matches!(event::read(), Ok(Event::Key(_))) {}
+            }
+            terminal::disable_raw_mode().unwrap();
+
+            if key_pressed {
+                break;
+            }
+        }
+    }
+
+    pub fn run(&mut self) {
+        let last = self.positions.len() - 1;
+
+        loop {
+            self.show();
+
+            print!("[Enter/n] next, [p] previous, [g N] go to move N, [a ms] autoplay, [q] quit: ");
+            stdout().flush().unwrap();
+
+            let mut command = String::new();
+            if stdin().read_line(&mut command).unwrap_or(0) == 0 {
+                break; // EOF
+            }
+            let mut command = command.split_whitespace();
+
+            match (command.next(), command.next()) {
+                (None, _) | (Some("n"), _) => self.current = (self.current + 1).min(last),
+                (Some("p"), _) => self.current = self.current.saturating_sub(1),
+                (Some("g"), Some(n)) => match n.parse::<usize>() {
+                    Ok(n) => self.current = n.min(last),
+                    Err(_) => println!("Not a move number: {:?}", n),
+                },
+                (Some("a"), delay) => {
+                    let delay = delay.and_then(|ms| ms.parse::<u64>().ok()).unwrap_or(1000);
+                    self.autoplay(Duration::from_millis(delay));
+                }
+                (Some("q"), _) => break,
+                (Some(other), _) => {
+                    println!("Unknown command: {:?}", other);
+                    std::thread::sleep(Duration::from_secs(1));
+                }
+            }
+        }
+    }
+}
+
+impl Drop for Replay {
+    fn drop(&mut self) {
+        self.display.end_display();
+    }
+}
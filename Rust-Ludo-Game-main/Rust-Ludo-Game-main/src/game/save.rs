@@ -0,0 +1,114 @@
+use std::{fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use super::player::Player;
+use crate::engine::EngineState;
+
+// Increment this on any change in the saved format, older files will then be rejected instead of misread
+pub const SAVE_VERSION: u32 = 14;
+pub const DEFAULT_SAVE_FILE: &str = "ludo-save.json";
+
+#[derive(Serialize, Deserialize)]
+pub struct SavedGame {
+    pub version: u32,
+    pub players: Vec<Player>, // order matters !
+    pub engine: EngineState,  // with the turn in it, see `TurnState`
+    pub record: String, // game record till now, see game/record.rs for the format
+    pub pending_roll: Option<Vec<u8>>, // roll of an undone turn, to be played again instead of rolling
+}
+
+impl SavedGame {
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+
+        fs::write(path, json).map_err(|e| format!("Couldn't write {:?}: {}", path, e))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let json = fs::read_to_string(path).map_err(|e| format!("Couldn't read {:?}: {}", path, e))?;
+
+        // Check version first, a different version may not even parse as `SavedGame`
+        let version = serde_json::from_str::<serde_json::Value>(&json)
+            .map_err(|e| format!("Not a saved game: {}", e))?
+            .get("version")
+            .and_then(|v| v.as_u64());
+        if version != Some(SAVE_VERSION as u64) {
+            return Err(format!(
+                "Unsupported save version: {:?}, expected {}",
+                version, SAVE_VERSION
+            ));
+        }
+
+        let saved: SavedGame =
+            serde_json::from_str(&json).map_err(|e| format!("Not a saved game: {}", e))?;
+
+        if saved.players.is_empty() {
+            return Err("No players in saved game".to_string());
+        }
+
+        Ok(saved)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{dice::SeededDice, LudoEngine, Rang, Rules};
+
+    fn saved_game() -> SavedGame {
+        let mut engine = LudoEngine::from_notation(
+            "R:L,L,3,10/G:L,L,L/B:L,L,L,18 G 0/1/0",
+            Rules::preset("mensch").unwrap(),
+            Box::new(SeededDice::new(7)),
+        )
+        .unwrap();
+        engine.roll().unwrap();
+
+        SavedGame {
+            version: SAVE_VERSION,
+            players: [Rang::Red, Rang::Green, Rang::Blue]
+                .into_iter()
+                .map(|colour| Player { name: format!("{:?}", colour), colour, bot: None })
+                .collect(),
+            engine: engine.get_state(),
+            record: "[Result \"*\"]\n\n1. R6:U\n".to_string(),
+            pending_roll: Some(vec![4]),
+        }
+    }
+
+    #[test]
+    fn save_and_load() {
+        let path = std::env::temp_dir().join("ludo-save-test.json");
+        let saved = saved_game();
+        saved.save(&path).unwrap();
+        let loaded = SavedGame::load(&path);
+        fs::remove_file(&path).unwrap();
+        let loaded = loaded.unwrap();
+
+        assert_eq!(loaded.engine, saved.engine);
+        assert_eq!((loaded.record, loaded.pending_roll), (saved.record, saved.pending_roll));
+        let colours: Vec<Rang> = loaded.players.iter().map(|p| p.colour).collect();
+        assert_eq!(colours, [Rang::Red, Rang::Green, Rang::Blue]);
+
+        // Same seed, so the rolls carry on from where they were
+        let engine = LudoEngine::from_state(&loaded.engine, Box::new(SeededDice::new(7))).unwrap();
+        assert_eq!(engine.get_state(), saved.engine);
+    }
+
+    #[test]
+    fn other_versions_are_rejected() {
+        let path = std::env::temp_dir().join("ludo-save-version-test.json");
+        let mut saved = saved_game();
+        saved.version = SAVE_VERSION - 1;
+        saved.save(&path).unwrap();
+        let old = SavedGame::load(&path);
+
+        fs::write(&path, "{\"version\": 1, \"gotis\": []}").unwrap();
+        let older = SavedGame::load(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert!(matches!(old, Err(e) if e.starts_with("Unsupported save version")));
+        assert!(matches!(older, Err(e) if e.starts_with("Unsupported save version")));
+    }
+}
@@ -0,0 +1,240 @@
+use std::{thread, time::Instant};
+
+use crate::bot::{new_bot, Strategy, BOT_KINDS};
+use crate::engine::{dice::SeededDice, LudoEngine, Move, Rang, Rules, TurnState};
+
+// A game that goes on for this many rolls is given up as a draw, so a bad bot can't hang the run
+const MAX_ROLLS: u32 = 20_000;
+
+// Engine refuses a step of the turn only if the loop playing it is wrong
+const BUG: &str = "Engine refused a step of the turn... this is a bug, please report at https://github.com/ludo-game-self.engine/issues";
+
+// Runs games between bots, without any display or waiting, and prints the stats
+pub struct Simulation {
+    bots: Vec<String>, // bot for each seat, in colour order Red, Green, Yellow, Blue
+    rules: Rules,
+    games: u32,
+    threads: usize,
+    seed: u64,         // game `i` uses dice with seed `seed + i`
+    rotate: bool,      // bots change seats every game, so none gets the first roll more often
+}
+
+// What happened in one game
+struct GameStats {
+    order: Vec<usize>,   // bots in order of finishing (index in `Simulation::bots`)
+    winners: Vec<usize>, // both partners of the winning team with `Rules::teams`, empty if given up
+    turns: u32,          // a turn ends when the dice goes to the next player
+    captures: u32,
+    given_up: bool,      // still going after `MAX_ROLLS`
+}
+
+// Mean and half width of its 95% confidence interval
+struct Estimate {
+    mean: f64,
+    margin: f64,
+}
+
+impl Estimate {
+    fn of(values: &[f64]) -> Self {
+        let n = values.len() as f64;
+        if values.is_empty() {
+            return Estimate { mean: 0.0, margin: 0.0 };
+        }
+
+        let mean = values.iter().sum::<f64>() / n;
+        let variance = if values.len() > 1 {
+            values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0)
+        } else {
+            0.0
+        };
+
+        Estimate {
+            mean,
+            margin: 1.96 * (variance / n).sqrt(),
+        }
+    }
+}
+
+impl Simulation {
+    pub fn new(bots: Vec<String>, rules: Rules, games: u32, threads: usize, seed: u64, rotate: bool) -> Result<Self, String> {
+        if !(2..=4).contains(&bots.len()) {
+            return Err(format!("Need 2 to 4 bots, got {}", bots.len()));
+        }
+        if threads == 0 {
+            return Err("Need at least 1 thread".to_string());
+        }
+        rules.validate()?;
+
+        for spec in bots.iter() {
+            if new_bot(spec, 0).is_none() {
+                return Err(format!("Unknown bot: {:?}, choose from {:?}", spec, BOT_KINDS));
+            }
+        }
+
+        Ok(Simulation {
+            bots,
+            rules,
+            games,
+            threads,
+            seed,
+            rotate,
+        })
+    }
+
+    fn play_game(&self, game: u32) -> GameStats {
+        let colours = [Rang::Red, Rang::Green, Rang::Yellow, Rang::Blue];
+        let n = self.bots.len();
+
+        // `seats[i]` is the bot playing i'th colour
+        let shift = if self.rotate { game as usize % n } else { 0 };
+        let seats: Vec<usize> = (0..n).map(|i| (i + shift) % n).collect();
+
+        let seed = self.seed.wrapping_add(game as u64);
+        // SAFETY: Rules were checked in `Simulation::new()`, and colours are distinct
+        let mut engine = LudoEngine::new(colours[..n].to_vec(), self.rules.clone(), Box::new(SeededDice::new(seed))).unwrap();
+
+        // SAFETY: All specs were checked in `Simulation::new()`
+        let mut bots: Vec<Box<dyn Strategy>> = seats
+            .iter()
+            .enumerate()
+            .map(|(i, bot)| new_bot(&self.bots[*bot], seed.wrapping_add(i as u64)).unwrap())
+            .collect();
+
+        let mut stats = GameStats {
+            order: Vec::new(),
+            winners: Vec::new(),
+            turns: 0,
+            captures: 0,
+            given_up: true,
+        };
+        let mut rolls = 0;
+
+        loop {
+            match engine.get_turn_state() {
+                // Last one left has nothing to play for, see `Rules::stop_at_first` etc.
+                TurnState::GameOver => {
+                    stats.given_up = false;
+                    break;
+                }
+                TurnState::TurnOver => {
+                    let player = engine.current_player();
+                    engine.end_turn().expect(BUG);
+                    if engine.current_player() != player {
+                        stats.turns += 1;
+                    }
+                }
+                TurnState::AwaitingRoll => {
+                    if rolls == MAX_ROLLS {
+                        break;
+                    }
+                    rolls += 1;
+                    engine.roll().expect(BUG);
+                }
+                TurnState::AwaitingMove => {
+                    let plays = engine.get_plays();
+                    if plays.is_empty() {
+                        engine.pass().expect(BUG);
+                        continue;
+                    }
+
+                    let seat = colours.iter().position(|c| *c == engine.current_player()).unwrap();
+                    let i = bots[seat].choose_play(&engine, engine.get_current_colour(), &plays);
+                    let (value, mv) = &plays[i.min(plays.len() - 1)];
+
+                    engine.choose(*value, mv).expect(BUG);
+                    if let Move::Capture { victims, .. } = mv {
+                        stats.captures += victims.len() as u32;
+                    }
+                    // Turn that ended the game is not ended with `end_turn()`, it still counts
+                    if engine.get_turn_state() == TurnState::GameOver {
+                        stats.turns += 1;
+                    }
+                }
+            }
+        }
+
+        let seat_of = |colour: &Rang| seats[colours.iter().position(|c| c == colour).unwrap()];
+        stats.order = engine.get_finish_order().iter().map(seat_of).collect();
+        stats.winners = engine.get_winners().iter().map(seat_of).collect();
+
+        // Out of rounds, rest are placed by their points
+        if self.rules.rounds > 0 && !stats.given_up {
+            let mut rest: Vec<Rang> = colours[..n]
+                .iter()
+                .filter(|c| !engine.get_finish_order().contains(c))
+                .copied()
+                .collect();
+            rest.sort_by_key(|c| std::cmp::Reverse(engine.get_score(*c)));
+            stats.order.extend(rest.iter().map(seat_of));
+        }
+
+        stats
+    }
+
+    pub fn run(&self) {
+        let start = Instant::now();
+
+        // Game `i` is played by thread `i % threads`, each thread makes its own bots and engines
+        let results: Vec<GameStats> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..self.threads)
+                .map(|t| {
+                    scope.spawn(move || {
+                        (0..self.games)
+                            .filter(|game| *game as usize % self.threads == t)
+                            .map(|game| self.play_game(game))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+
+            handles.into_iter().flat_map(|h| h.join().unwrap()).collect()
+        });
+
+        println!(
+            "{} games in {:.1?} ({} rules, seed {}, {} threads{})",
+            results.len(),
+            start.elapsed(),
+            self.rules.name,
+            self.seed,
+            self.threads,
+            if self.rotate { ", rotating seats" } else { "" }
+        );
+
+        let estimate = |value: &dyn Fn(&GameStats) -> f64| {
+            Estimate::of(&results.iter().map(value).collect::<Vec<_>>())
+        };
+
+        for (i, spec) in self.bots.iter().enumerate() {
+            let label = if self.rotate {
+                format!("{}. {}", i + 1, spec)
+            } else {
+                format!("{:?} {}", [Rang::Red, Rang::Green, Rang::Yellow, Rang::Blue][i], spec)
+            };
+
+            let wins = estimate(&|g| if g.winners.contains(&i) { 1.0 } else { 0.0 });
+            let place = estimate(&|g| match g.order.iter().position(|b| *b == i) {
+                Some(place) => place as f64 + 1.0,
+                None => self.bots.len() as f64, // last, or game was given up
+            });
+
+            println!(
+                "  {:<30} wins {:5.1}% ± {:4.1}%   avg place {:.2} ± {:.2}",
+                label,
+                wins.mean * 100.0,
+                wins.margin * 100.0,
+                place.mean,
+                place.margin
+            );
+        }
+
+        let turns = estimate(&|g| g.turns as f64);
+        let captures = estimate(&|g| g.captures as f64);
+        let unfinished = results.iter().filter(|g| g.given_up).count();
+
+        println!("  Game length:       {:.1} ± {:.1} turns", turns.mean, turns.margin);
+        println!("  Captures per game: {:.2} ± {:.2}", captures.mean, captures.margin);
+        if unfinished > 0 {
+            println!("  {} games given up after {} rolls", unfinished, MAX_ROLLS);
+        }
+    }
+}
@@ -1,12 +1,122 @@
+mod bot;
 mod display;
 mod engine;
 mod game;
 
-use game::LudoGame as Ludo;
+use engine::dice::{Dice, LoadedDice, ScriptedDice, SeededDice, ThreadDice};
+use engine::Rules;
+use game::{LudoGame as Ludo, Replay, Simulation, StartFrom};
+use std::path::Path;
+
+// Usage:
+//   ludo                          random seed (printed at the end, to replay)
+//   ludo --seed 42                same rolls as an earlier game with seed 42
+//   ludo --dice 6,1,3             rolls 6, 1, 3 in a loop (for testing)
+//   ludo --loaded 1,1,1,1,1,5     biased dice, weight of each face 1 to 6
+//   ludo --unseeded               thread rng, game can NOT be replayed
+//   ludo --resume ludo-save.json  continue a saved game (type 'save' when asked to roll)
+//   ludo --position "R:L,L,0,23/B:L,L,L,L R 0/0"   start from a position (type 'pos' when asked to roll)
+//   ludo --replay ludo-game.lgn   step through a recorded game
+//   ludo --no-undo                players can NOT take back moves (type 'undo'/'redo' when asked to roll)
+//   ludo --rules house.json       play with house rules (see engine/rules.rs for the file format)
+//   ludo --rules parcheesi        play a variant, see `Rules::preset()` for the names
+//   ludo --rules quick            first to finish a goti wins, or `--rules score` for most points after 30 rounds
+//   ludo --rules teams            Red & Yellow against Green & Blue
+//
+//   ludo simulate --bots mcts,safe,capture,random [--games 1000] [--threads 8] [--seed 42] [--rotate] [--rules house.json]
+//                                 bot games without display, prints win rates etc. (see bot.rs for bots)
+fn value_of(args: &[String], flag: &str) -> Option<String> {
+    args.iter()
+        .position(|a| a == flag)
+        .map(|i| args.get(i + 1).unwrap_or_else(|| panic!("Missing value for {}", flag)).clone())
+}
+
+// Comma separated numbers, each must fit in `T` (eg. '262' is not a roll of the dice)
+fn parse_list<T: std::str::FromStr>(list: &str) -> Vec<T> {
+    list.split(',')
+        .map(|n| n.trim().parse().unwrap_or_else(|_| panic!("Not a number, or too big: {:?}", n)))
+        .collect()
+}
+
+fn get_dice(args: &[String]) -> Box<dyn Dice> {
+    let value_of = |flag: &str| value_of(args, flag);
+
+    let seed = value_of("--seed").map(|s| s.parse::<u64>().expect("Seed must be a number"));
+
+    if args.iter().any(|a| a == "--unseeded") {
+        Box::new(ThreadDice)
+    } else if let Some(rolls) = value_of("--dice") {
+        Box::new(ScriptedDice::new(parse_list(&rolls)).unwrap_or_else(|e| panic!("{}", e)))
+    } else if let Some(weights) = value_of("--loaded") {
+        let weights: [u32; 6] = parse_list(&weights).try_into().expect("Need exactly 6 weights");
+        Box::new(LoadedDice::new(weights, seed.unwrap_or_else(rand::random)).unwrap_or_else(|e| panic!("{}", e)))
+    } else {
+        match seed {
+            Some(seed) => Box::new(SeededDice::new(seed)),
+            None => Box::new(SeededDice::from_entropy()),
+        }
+    }
+}
+
+fn get_rules(args: &[String]) -> Rules {
+    match value_of(args, "--rules") {
+        Some(name) if Rules::preset(&name).is_some() => Rules::preset(&name).unwrap(),
+        Some(path) => Rules::load(Path::new(&path)).unwrap_or_else(|e| panic!("{}", e)),
+        None => Rules::default(),
+    }
+}
+
+fn simulate(args: &[String]) {
+    let value_of = |flag: &str| value_of(args, flag);
+    let number = |flag: &str, default: u64| -> u64 {
+        value_of(flag)
+            .map(|n| n.parse().unwrap_or_else(|_| panic!("{} must be a number", flag)))
+            .unwrap_or(default)
+    };
+
+    let bots: Vec<String> = value_of("--bots")
+        .expect("Give bots to play with `--bots`, eg. `--bots mcts,safe`")
+        .split(',')
+        .map(|b| b.trim().to_string())
+        .collect();
+    let threads = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+
+    let simulation = Simulation::new(
+        bots,
+        get_rules(args),
+        number("--games", 100) as u32,
+        number("--threads", threads as u64) as usize,
+        number("--seed", rand::random()),
+        args.iter().any(|a| a == "--rotate"),
+    )
+    .unwrap_or_else(|e| panic!("{}", e));
+
+    simulation.run();
+}
 
 fn main() {
-    let mut ludo = Ludo::new();
-    ludo.play();
+    let args: Vec<String> = std::env::args().collect();
 
-    std::thread::sleep(std::time::Duration::from_secs(5));
+    if args.get(1).map(String::as_str) == Some("simulate") {
+        simulate(&args);
+        return;
+    }
+
+    if let Some(path) = value_of(&args, "--replay") {
+        Replay::new(Path::new(&path)).run();
+        return;
+    }
+
+    let resume_from = value_of(&args, "--resume");
+    let position = value_of(&args, "--position");
+
+    let start = match (&resume_from, &position) {
+        (Some(path), _) => StartFrom::SavedGame(Path::new(path)),
+        (None, Some(notation)) => StartFrom::Position(notation),
+        (None, None) => StartFrom::NewGame,
+    };
+
+    let mut ludo = Ludo::new(get_dice(&args), get_rules(&args), start);
+    ludo.set_allow_undo(!args.iter().any(|a| a == "--no-undo"));
+    ludo.play();
 }